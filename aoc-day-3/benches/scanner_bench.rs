@@ -0,0 +1,47 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use memory_diagnostics::MemoryParser;
+use std::{fs, time::Duration};
+
+fn bench_memory_scan(c: &mut Criterion) {
+    let dump = fs::read("memory_dump.txt").expect("Failed to read memory dump");
+
+    // Mostly noise, like the large corrupted dumps seen in the field
+    let mut sparse = vec![b'#'; 1 << 20];
+    for (i, chunk) in sparse.chunks_mut(4096).enumerate() {
+        let instruction: &[u8] = if i % 2 == 0 {
+            b"mul(12,34)"
+        } else {
+            b"don't()"
+        };
+        chunk[..instruction.len()].copy_from_slice(instruction);
+    }
+
+    for (name, input) in [("memory_dump", &dump), ("sparse_dump", &sparse)] {
+        let mut group = c.benchmark_group(name);
+        group.measurement_time(Duration::from_secs(10));
+        group.throughput(Throughput::Bytes(input.len() as u64));
+
+        group.bench_function("process_char", |b| {
+            b.iter(|| {
+                let mut parser = MemoryParser::default();
+                for &byte in input.iter() {
+                    parser.process_char(byte as char);
+                }
+                black_box(parser.checksums())
+            })
+        });
+
+        group.bench_function("process_bytes", |b| {
+            b.iter(|| {
+                let mut parser = MemoryParser::default();
+                parser.process_bytes(input);
+                black_box(parser.checksums())
+            })
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_memory_scan);
+criterion_main!(benches);
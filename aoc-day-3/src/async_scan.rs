@@ -0,0 +1,136 @@
+// async_scan.rs
+use crate::parser::{Checksums, Instruction, MemoryParser, ParserConfig};
+use futures_core::Stream;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+// Corrupted memory read per chunk, matching the blocking scanner
+const CHUNK_SIZE: usize = 1024;
+
+// Instructions buffered between the background scan and its consumer
+const STREAM_CAPACITY: usize = 1024;
+
+// Scan an async memory dump to EOF and return both checksums
+pub async fn scan_async<R>(mut reader: R, config: ParserConfig) -> io::Result<Checksums>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut parser = MemoryParser::with_config(config);
+    let mut buffer = [0; CHUNK_SIZE];
+
+    loop {
+        let bytes_read = reader.read(&mut buffer).await?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        parser.process_bytes(&buffer[..bytes_read]);
+    }
+
+    Ok(parser.checksums())
+}
+
+// Scan an async memory dump on a background task
+pub fn spawn_scan<R>(reader: R, config: ParserConfig) -> JoinHandle<io::Result<Checksums>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(scan_async(reader, config))
+}
+
+// Stream of instructions recognized by a background scan
+pub struct InstructionStream {
+    receiver: mpsc::Receiver<io::Result<Instruction>>,
+}
+
+impl InstructionStream {
+    // Start scanning on a background task, the stream ends at EOF
+    pub fn spawn<R>(mut reader: R, config: ParserConfig) -> Self
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(STREAM_CAPACITY);
+
+        tokio::spawn(async move {
+            let mut parser = MemoryParser::with_config(config);
+            let mut buffer = [0; CHUNK_SIZE];
+
+            loop {
+                let bytes_read = match reader.read(&mut buffer).await {
+                    Ok(0) => break,
+                    Ok(bytes_read) => bytes_read,
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        break;
+                    }
+                };
+
+                for &byte in &buffer[..bytes_read] {
+                    if let Some(instruction) = parser.process_char(byte as char) {
+                        // Stop scanning once nobody is listening
+                        if sender.send(Ok(instruction)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        InstructionStream { receiver }
+    }
+
+    // Wait for the next recognized instruction
+    pub async fn next(&mut self) -> Option<io::Result<Instruction>> {
+        self.receiver.recv().await
+    }
+}
+
+impl Stream for InstructionStream {
+    type Item = io::Result<Instruction>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONDITIONAL_EXAMPLE: &[u8] =
+        b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)do()?mul(8,5))";
+
+    #[tokio::test]
+    async fn test_spawned_scan_checksums() {
+        let checksums = spawn_scan(CONDITIONAL_EXAMPLE, ParserConfig::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(checksums.unconditional, 161);
+        assert_eq!(checksums.conditional, 48);
+    }
+
+    #[tokio::test]
+    async fn test_instruction_stream() {
+        let mut stream = InstructionStream::spawn(CONDITIONAL_EXAMPLE, ParserConfig::default());
+        let mut instructions = Vec::new();
+        while let Some(instruction) = stream.next().await {
+            instructions.push(instruction.unwrap());
+        }
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction::Mul { x: 2, y: 4 },
+                Instruction::Dont,
+                Instruction::Mul { x: 5, y: 5 },
+                Instruction::Mul { x: 11, y: 8 },
+                Instruction::Do,
+                Instruction::Mul { x: 8, y: 5 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+// generator.rs
+use crate::parser::Checksums;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::io::{self, Write};
+
+// Filler bytes that can never start or continue an instruction: none of them
+// appear in mul(X,Y), do() or don't(), signs and digits included
+const FILLER: &[u8] = b"!@#$%^&*[]{}<>?+_~ ;:/\\|xyzwhywhereswhichsawarise";
+
+// Share of valid instructions that are mul(), the rest split between do() and don't()
+const MUL_SHARE: f64 = 0.8;
+
+// Near-miss instruction shapes, `X` and `Y` are replaced by random operands
+const NEAR_MISSES: &[&str] = &[
+    "mul(X,Y]",
+    "mul[X,Y)",
+    "mul (X,Y)",
+    "mul(X, Y)",
+    "mul(X,Y",
+    "mul(-X,Y)",
+    "mul(X;Y)",
+    "Mul(X,Y)",
+    "mull(X,Y)",
+    "mu(X,Y)",
+    "mul(XXXX,Y)",
+    "mul(X,YYYY)",
+    "do( )",
+    "do(",
+    "don't",
+    "dont()",
+    "don'()",
+    "do_not()",
+    "Do()",
+];
+
+// Settings for a synthetic corrupted memory dump
+#[derive(Copy, Clone, Debug)]
+pub struct DumpGenerator {
+    seed: u64,         // Seed for reproducible dumps
+    size: usize,       // Exact size of the dump in bytes
+    signal_ratio: f64, // Share of tokens that are valid instructions
+    max_gap: usize,    // Most filler bytes between two tokens
+}
+
+impl DumpGenerator {
+    pub fn new(seed: u64) -> Self {
+        DumpGenerator {
+            seed,
+            size: 16 * 1024,
+            signal_ratio: 0.5,
+            max_gap: 8,
+        }
+    }
+
+    // Exact size of the generated dump in bytes
+    pub fn size(mut self, bytes: usize) -> Self {
+        self.size = bytes;
+        self
+    }
+
+    // Share of tokens that are valid instructions rather than near misses,
+    // clamped to 0.0..=1.0. Panics on NaN, which is no share at all
+    pub fn signal_ratio(mut self, ratio: f64) -> Self {
+        assert!(!ratio.is_nan(), "signal ratio must be a number, got NaN");
+        self.signal_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    // Most filler bytes placed between two tokens
+    pub fn max_gap(mut self, bytes: usize) -> Self {
+        self.max_gap = bytes;
+        self
+    }
+
+    // Generate the whole dump in memory
+    pub fn generate(&self) -> GeneratedDump {
+        let mut bytes = Vec::with_capacity(self.size);
+        let summary = self
+            .generate_into(&mut bytes)
+            .expect("writing to a Vec cannot fail");
+        GeneratedDump { bytes, summary }
+    }
+
+    // Stream the dump into a writer, for dumps too large to keep in memory
+    pub fn generate_into<W: Write>(&self, mut writer: W) -> io::Result<DumpSummary> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut summary = DumpSummary::default();
+        let mut enabled = true;
+        let mut written = 0;
+        let mut token = Vec::new();
+
+        loop {
+            token.clear();
+            for _ in 0..rng.gen_range(0..=self.max_gap) {
+                token.push(*FILLER.choose(&mut rng).unwrap());
+            }
+
+            let instruction = if rng.gen_bool(self.signal_ratio) {
+                Some(push_instruction(&mut rng, &mut token))
+            } else {
+                push_near_miss(&mut rng, &mut token);
+                None
+            };
+
+            // Stop before a token would be cut off by the end of the dump
+            if written + token.len() > self.size {
+                break;
+            }
+
+            writer.write_all(&token)?;
+            written += token.len();
+
+            match instruction {
+                Some(Token::Mul(product)) => {
+                    summary.instructions += 1;
+                    summary.checksums.unconditional += product;
+                    if enabled {
+                        summary.checksums.conditional += product;
+                    }
+                }
+                Some(Token::Do) => {
+                    summary.instructions += 1;
+                    enabled = true;
+                }
+                Some(Token::Dont) => {
+                    summary.instructions += 1;
+                    enabled = false;
+                }
+                None => summary.near_misses += 1,
+            }
+        }
+
+        // Pad the tail with filler to reach the exact size
+        token.clear();
+        token.extend((written..self.size).map(|_| *FILLER.choose(&mut rng).unwrap()));
+        writer.write_all(&token)?;
+        summary.size = self.size;
+
+        Ok(summary)
+    }
+}
+
+// Valid instruction placed in the dump
+enum Token {
+    Mul(i128),
+    Do,
+    Dont,
+}
+
+fn push_instruction(rng: &mut ChaCha8Rng, token: &mut Vec<u8>) -> Token {
+    if rng.gen_bool(MUL_SHARE) {
+        let x = rng.gen_range(0..=999);
+        let y = rng.gen_range(0..=999);
+        write!(token, "mul({},{})", x, y).unwrap();
+        Token::Mul(x * y)
+    } else if rng.gen_bool(0.5) {
+        token.extend_from_slice(b"do()");
+        Token::Do
+    } else {
+        token.extend_from_slice(b"don't()");
+        Token::Dont
+    }
+}
+
+fn push_near_miss(rng: &mut ChaCha8Rng, token: &mut Vec<u8>) {
+    let shape = NEAR_MISSES.choose(rng).unwrap();
+    let shape = shape
+        .replace("XXXX", &rng.gen_range(1000..=9999).to_string())
+        .replace("YYYY", &rng.gen_range(1000..=9999).to_string())
+        .replace('X', &rng.gen_range(0..=999).to_string())
+        .replace('Y', &rng.gen_range(0..=999).to_string());
+    token.extend_from_slice(shape.as_bytes());
+
+    // Close the near miss so it cannot swallow the start of the next token
+    token.push(*FILLER.choose(rng).unwrap());
+}
+
+// What the generator placed in a dump
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DumpSummary {
+    pub size: usize,          // Dump size in bytes
+    pub instructions: usize,  // Valid mul(), do() and don't() instructions
+    pub near_misses: usize,   // Corrupted instructions that must be ignored
+    pub checksums: Checksums, // Checksums a correct parser must report
+}
+
+// Synthetic corrupted memory dump with its expected checksums
+#[derive(Clone, Debug)]
+pub struct GeneratedDump {
+    pub bytes: Vec<u8>,
+    pub summary: DumpSummary,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::MemoryParser;
+
+    #[test]
+    fn test_generated_checksums_match_parser() {
+        for (seed, ratio) in [(1, 0.0), (2, 0.1), (3, 0.5), (4, 0.9), (5, 1.0)] {
+            for max_gap in [0, 8] {
+                let dump = DumpGenerator::new(seed)
+                    .size(64 * 1024)
+                    .signal_ratio(ratio)
+                    .max_gap(max_gap)
+                    .generate();
+                assert_eq!(dump.bytes.len(), 64 * 1024);
+
+                let mut parser = MemoryParser::default();
+                let mut instructions = 0;
+                for &byte in &dump.bytes {
+                    instructions += parser.process_char(byte as char).is_some() as usize;
+                }
+                assert_eq!(parser.checksums(), dump.summary.checksums);
+                assert_eq!(instructions, dump.summary.instructions);
+            }
+        }
+    }
+
+    #[test]
+    fn test_filler_cannot_form_instructions() {
+        for byte in FILLER {
+            assert!(
+                !b"mul(,)don't-0123456789".contains(byte),
+                "filler byte {:?} can continue an instruction",
+                *byte as char
+            );
+        }
+
+        // Filler alone, and filler after every near-miss prefix, is never parsed
+        let mut parser = MemoryParser::default();
+        for &byte in FILLER {
+            assert!(parser.process_char(byte as char).is_none());
+        }
+        for prefix in [
+            "m", "mu", "mul(", "mul(1", "mul(1,", "mul(1,2", "d", "do", "do(",
+        ] {
+            for prefix in [prefix.to_string(), prefix.replace("do", "don't")] {
+                for &byte in FILLER {
+                    let mut parser = MemoryParser::default();
+                    for c in prefix.chars().chain([byte as char, ')', '(', ')']) {
+                        assert!(
+                            parser.process_char(c).is_none(),
+                            "{}{}",
+                            prefix,
+                            byte as char
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "NaN")]
+    fn test_nan_signal_ratio_is_rejected() {
+        DumpGenerator::new(1).signal_ratio(f64::NAN);
+    }
+
+    #[test]
+    fn test_generation_is_reproducible() {
+        let first = DumpGenerator::new(2024).size(4096).generate();
+        let second = DumpGenerator::new(2024).size(4096).generate();
+        let other = DumpGenerator::new(2025).size(4096).generate();
+        assert_eq!(first.bytes, second.bytes);
+        assert_eq!(first.summary, second.summary);
+        assert_ne!(first.bytes, other.bytes);
+    }
+}
@@ -1,5 +1,16 @@
+pub mod async_scan;
+pub mod generator;
 pub mod memory;
 pub mod parser;
+pub mod replay;
 
-pub use memory::{MemoryDigit, MemoryNumber, ValidMemoryValue};
-pub use parser::{MemoryParser, ParserState};
+pub use async_scan::{scan_async, spawn_scan, InstructionStream};
+pub use generator::{DumpGenerator, DumpSummary, GeneratedDump};
+pub use memory::{
+    MemoryDigit, MemoryDigitError, MemoryNumber, MemoryValueError, OperandLimit, OperandLimitError,
+    ValidMemoryValue,
+};
+pub use parser::{
+    Checksums, Instruction, MemoryParser, ParserConfig, ParserMode, ParserState, ScanProgress,
+};
+pub use replay::{find_divergence, Divergence, Replay, ReplaySteps, StopAt, TraceStep};
@@ -1,42 +1,91 @@
-use memory_diagnostics::MemoryParser;
+use memmap2::Mmap;
+use memory_diagnostics::{MemoryParser, Replay};
 use std::{
     env,
     fs::File,
     io::{self, Read},
+    time::Duration,
 };
 
+// How often a memory-mapped scan reports progress on stderr
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+const USAGE: &str = "Usage: program [--mmap] [--trace [--stop-offset <bytes>] [--stop-after <count>]] <memory-dump-path>";
+
 fn main() -> io::Result<()> {
-    let mut args = env::args_os();
-    args.next(); // Skip program name
-    let filename = match args.next() {
+    let mut use_mmap = false;
+    let mut replay = None;
+    let mut filename = None;
+    let mut args = env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--mmap" {
+            use_mmap = true;
+        } else if arg == "--trace" {
+            replay.get_or_insert_with(Replay::default);
+        } else if arg == "--stop-offset" || arg == "--stop-after" {
+            let Some(limit) = args.next().and_then(|n| n.to_str()?.parse().ok()) else {
+                eprintln!("❄️ Error: {} needs a number!", arg.to_string_lossy());
+                eprintln!("{}", USAGE);
+                return Ok(());
+            };
+            let trace = replay.unwrap_or_default();
+            replay = Some(if arg == "--stop-offset" {
+                trace.stop_at_offset(limit)
+            } else {
+                trace.stop_after(limit)
+            });
+        } else {
+            filename = Some(arg);
+        }
+    }
+
+    let filename = match filename {
         Some(f) => f,
         None => {
             eprintln!("❄️ Error: No corrupted memory file provided!");
-            eprintln!("Usage: program <memory-dump-path>");
+            eprintln!("{}", USAGE);
             return Ok(());
         }
     };
 
     let mut file = File::open(filename)?;
     let mut parser = MemoryParser::default();
-    let mut buffer = [0; 1024]; // Read corrupted memory in 1KB chunks
 
-    loop {
-        let bytes_read = file.read(&mut buffer)?;
-        if bytes_read == 0 {
-            break;
-        }
+    if let Some(replay) = replay {
+        // Safety: the dump is only read, and must not be truncated while mapped
+        let dump = unsafe { Mmap::map(&file)? };
+        let checksums = replay.write_trace(&dump, io::stdout().lock())?;
+        println!("\n🧾 Replay Finished!");
+        println!("✨ Unconditional Checksum: {}", checksums.unconditional);
+        println!("🎛️ Conditional Checksum: {}", checksums.conditional);
+        return Ok(());
+    }
 
-        for &byte in &buffer[..bytes_read] {
-            let c = byte as char;
-            parser.process_char(c);
+    if use_mmap {
+        // Safety: the dump is only read, and must not be truncated while mapped
+        let dump = unsafe { Mmap::map(&file)? };
+        parser.process_bytes_with_progress(&dump, PROGRESS_INTERVAL, |progress| {
+            eprintln!("📡 {}", progress);
+        });
+    } else {
+        let mut buffer = [0; 1024]; // Read corrupted memory in 1KB chunks
+
+        loop {
+            let bytes_read = file.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            parser.process_bytes(&buffer[..bytes_read]);
         }
     }
 
     println!("\n🎅 North Pole Memory Analysis Complete! 🎄");
     println!("==========================================");
+    let checksums = parser.checksums();
     println!("🔍 Corrupted Memory Scan Results:");
-    println!("✨ Total Checksum: {}", parser.checksum());
+    println!("✨ Unconditional Checksum: {}", checksums.unconditional);
+    println!("🎛️ Conditional Checksum: {}", checksums.conditional);
     println!("==========================================\n");
 
     Ok(())
@@ -45,6 +94,10 @@ fn main() -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use memory_diagnostics::{
+        Checksums, MemoryDigit, MemoryDigitError, MemoryNumber, MemoryValueError, OperandLimit,
+        OperandLimitError, ParserConfig, ParserMode, ValidMemoryValue,
+    };
 
     #[test]
     fn test_shopkeeper_example() {
@@ -53,7 +106,7 @@ mod tests {
         for c in input.chars() {
             parser.process_char(c);
         }
-        assert_eq!(parser.checksum(), &161_u32);
+        assert_eq!(parser.checksum(), &161_i128);
     }
 
     #[test]
@@ -63,6 +116,181 @@ mod tests {
         for c in input.chars() {
             parser.process_char(c);
         }
-        assert_eq!(parser.checksum(), &48_u32);
+        assert_eq!(parser.checksum(), &48_i128);
+    }
+
+    fn scan(input: &str, config: ParserConfig) -> i128 {
+        let mut parser = MemoryParser::with_config(config);
+        for c in input.chars() {
+            parser.process_char(c);
+        }
+        *parser.checksum()
+    }
+
+    #[test]
+    fn test_operand_limits() {
+        let input = "mul(1000,2)mul(12,3)mul(0012,2)";
+        assert_eq!(scan(input, ParserConfig::default()), 60);
+        assert_eq!(scan(input, ParserConfig::default().max_value(9999)), 2060);
+        assert_eq!(
+            scan(input, ParserConfig::default().max_digits(2).unwrap()),
+            36
+        );
+        assert_eq!(scan(input, ParserConfig::default().max_value(5)), 0);
+
+        // Digit limits outside 1..=9 are rejected rather than clamped
+        assert_eq!(
+            ParserConfig::default().max_digits(0).unwrap_err(),
+            OperandLimitError::Digits(0)
+        );
+        assert!(ParserConfig::default().max_digits(10).is_err());
+        assert!(ParserConfig::default().max_digits(9).is_ok());
+
+        // Products above i64::MAX are kept exactly
+        let wide = ParserConfig::default().max_value(u32::MAX);
+        assert_eq!(
+            scan("mul(4294967295,4294967295)mul(4294967295,4294967295)", wide),
+            2 * 4_294_967_295_i128 * 4_294_967_295
+        );
+    }
+
+    #[test]
+    fn test_operand_encoding() {
+        let input = "mul(-3,4)mul(07,2)mul(0,5)mul(5,-0)";
+        assert_eq!(scan(input, ParserConfig::default()), 14);
+        assert_eq!(scan(input, ParserConfig::default().allow_signed(true)), 2);
+        let strict = ParserConfig::default().allow_leading_zeros(false);
+        assert_eq!(scan(input, strict), 0);
+    }
+
+    #[test]
+    fn test_both_checksums_in_one_pass() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)do()?mul(8,5))";
+        let mut parser = MemoryParser::default();
+        for c in input.chars() {
+            parser.process_char(c);
+        }
+        assert_eq!(
+            parser.checksums(),
+            Checksums {
+                unconditional: 161,
+                conditional: 48,
+            }
+        );
+
+        let unconditional = ParserConfig::default().mode(ParserMode::Unconditional);
+        assert_eq!(scan(input, unconditional), 161);
+        assert_eq!(scan(input, ParserConfig::default()), 48);
+    }
+
+    #[test]
+    fn test_start_disabled() {
+        let input = "mul(2,3)do()mul(4,5)";
+        assert_eq!(
+            scan(input, ParserConfig::default().start_enabled(false)),
+            20
+        );
+    }
+
+    #[test]
+    fn test_skip_ahead_matches_char_scan() {
+        let dump = include_str!("../memory_dump.txt");
+        let inputs = [
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)do()?mul(8,5))",
+            "mmul(mul(1,mul(2,3)dddo()don'tdon't()mul(4,5)",
+            dump,
+        ];
+        for input in inputs {
+            let mut expected = MemoryParser::default();
+            for c in input.chars() {
+                expected.process_char(c);
+            }
+
+            // Chunk boundaries can fall anywhere inside an instruction
+            for chunk_size in [1, 3, 7, 1024] {
+                let mut parser = MemoryParser::default();
+                for chunk in input.as_bytes().chunks(chunk_size) {
+                    parser.process_bytes(chunk);
+                }
+                assert_eq!(parser.checksums(), expected.checksums());
+            }
+        }
+    }
+
+    #[test]
+    fn test_progress_reports() {
+        let input = "mul(2,4)do()don't()mul(3,3)".repeat(100_000);
+        let mut parser = MemoryParser::default();
+        let mut reports = Vec::new();
+        parser.process_bytes_with_progress(input.as_bytes(), Duration::ZERO, |progress| {
+            reports.push(*progress)
+        });
+
+        let last = reports.last().unwrap();
+        assert!(reports.len() > 1);
+        assert!(last.is_complete());
+        assert_eq!(last.instructions, 400_000);
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].bytes_scanned < pair[1].bytes_scanned));
+    }
+
+    #[test]
+    fn test_memory_errors() {
+        assert_eq!(
+            MemoryDigit::try_from('x'),
+            Err(MemoryDigitError::NotADigit('x'))
+        );
+
+        let mut number = MemoryNumber::from(MemoryDigit::try_from('1').unwrap());
+        for c in "000".chars() {
+            number += MemoryDigit::try_from(c).unwrap();
+        }
+        let err = ValidMemoryValue::try_from(number).unwrap_err();
+        assert_eq!(
+            err,
+            MemoryValueError::OutOfRange {
+                value: 1000,
+                digits: 4,
+                limit: OperandLimit::Value(999),
+            }
+        );
+        assert!(err.to_string().contains("1000"));
+
+        let valid = ParserConfig::default()
+            .max_digits(4)
+            .unwrap()
+            .validate(number)
+            .unwrap();
+        assert_eq!(valid.value(), 1000);
+
+        let mut padded = MemoryNumber::from(MemoryDigit::try_from('0').unwrap());
+        padded += MemoryDigit::try_from('7').unwrap();
+        let strict = ParserConfig::default().allow_leading_zeros(false);
+        assert_eq!(
+            strict.validate(padded).unwrap_err(),
+            MemoryValueError::LeadingZero {
+                value: 7,
+                digits: 2
+            }
+        );
+
+        // Twenty nines saturate the magnitude, which must not read as negative
+        let mut huge = MemoryNumber::from(MemoryDigit::try_from('9').unwrap());
+        for _ in 1..20 {
+            huge += MemoryDigit::try_from('9').unwrap();
+        }
+        assert_eq!(huge.value(), None);
+        assert!(huge > i64::MAX);
+        let err = ValidMemoryValue::try_from(huge).unwrap_err();
+        assert_eq!(
+            err,
+            MemoryValueError::Overflow {
+                digits: 20,
+                negative: false
+            }
+        );
+        assert!(!err.to_string().contains('-'));
+        assert_eq!((-huge).value(), None);
     }
 }
@@ -1,22 +1,43 @@
+// memory/digit.rs
+use std::fmt;
+
 // Memory digit found in corrupted data
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct MemoryDigit(u8);
 
+// Reasons a raw memory value cannot become a digit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryDigitError {
+    NotADigit(char), // Character outside '0'..='9'
+}
+
+impl fmt::Display for MemoryDigitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryDigitError::NotADigit(c) => {
+                write!(f, "corrupted memory value {:?} is not a digit", c)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MemoryDigitError {}
+
 // Convert raw memory values to digits
 impl TryFrom<char> for MemoryDigit {
-    type Error = ();
+    type Error = MemoryDigitError;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         if c.is_ascii_digit() {
             Ok(MemoryDigit(c as u8 - b'0'))
         } else {
-            Err(()) // Not a valid memory digit
+            Err(MemoryDigitError::NotADigit(c))
         }
     }
 }
 
-impl From<MemoryDigit> for u16 {
-    fn from(digit: MemoryDigit) -> u16 {
-        digit.0 as u16
+impl From<MemoryDigit> for u64 {
+    fn from(digit: MemoryDigit) -> u64 {
+        digit.0 as u64
     }
 }
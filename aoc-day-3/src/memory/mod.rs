@@ -3,9 +3,6 @@ mod digit;
 mod number;
 mod validation;
 
-pub use digit::MemoryDigit;
+pub use digit::{MemoryDigit, MemoryDigitError};
 pub use number::MemoryNumber;
-pub use validation::ValidMemoryValue;
-
-// Re-export the MAX_MEMORY_VALUE constant
-pub const MAX_MEMORY_VALUE: u16 = 999;
+pub use validation::{MemoryValueError, OperandLimit, OperandLimitError, ValidMemoryValue};
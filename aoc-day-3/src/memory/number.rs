@@ -1,39 +1,98 @@
 // memory/number.rs
 use super::digit::MemoryDigit;
 use std::cmp::Ordering;
-use std::ops::{AddAssign, Mul};
+use std::ops::{AddAssign, Mul, Neg};
 
 // Represents a number found in corrupted memory
-#[derive(Copy, Clone)]
-pub struct MemoryNumber(u16);
+#[derive(Copy, Clone, Debug)]
+pub struct MemoryNumber {
+    magnitude: u64,     // Absolute value of the digits read so far
+    digits: u8,         // Number of digits read, leading zeros included
+    negative: bool,     // Set when the operand carried a '-' sign
+    leading_zero: bool, // Set when a digit followed an initial '0'
+}
+
+impl MemoryNumber {
+    // Signed value of the memory number, None when it does not fit in an i64
+    pub fn value(&self) -> Option<i64> {
+        i64::try_from(self.signed()).ok()
+    }
+
+    // Signed value widened so that even a saturated magnitude fits
+    fn signed(&self) -> i128 {
+        let magnitude = i128::from(self.magnitude);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    // Absolute value, used when checking operand limits
+    pub fn magnitude(&self) -> u64 {
+        self.magnitude
+    }
+
+    // Number of digits read, leading zeros included
+    pub fn digits(&self) -> u8 {
+        self.digits
+    }
+
+    // Whether the number carried a '-' sign
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // Whether the number was written with a leading zero, like "007"
+    pub fn has_leading_zero(&self) -> bool {
+        self.leading_zero
+    }
+}
 
 // Implement multiplication for corrupted memory values
 impl Mul<&mut MemoryNumber> for &mut MemoryNumber {
-    type Output = u32;
+    type Output = i128;
 
-    fn mul(self, rhs: &mut MemoryNumber) -> u32 {
-        // Convert to u32 to prevent memory overflow during multiplication
-        self.0 as u32 * rhs.0 as u32
+    fn mul(self, rhs: &mut MemoryNumber) -> i128 {
+        // Operands are bounded by the parser config, so the product fits an i128
+        self.signed() * rhs.signed()
     }
 }
 
 // Convert single digits to memory numbers
 impl From<MemoryDigit> for MemoryNumber {
     fn from(digit: MemoryDigit) -> MemoryNumber {
-        MemoryNumber(digit.into())
+        MemoryNumber {
+            magnitude: digit.into(),
+            digits: 1,
+            negative: false,
+            leading_zero: false,
+        }
+    }
+}
+
+// Flip the sign of a memory number read after a '-'
+impl Neg for MemoryNumber {
+    type Output = MemoryNumber;
+
+    fn neg(self) -> MemoryNumber {
+        MemoryNumber {
+            negative: !self.negative,
+            ..self
+        }
     }
 }
 
 // Memory number comparison operations
-impl PartialEq<u16> for MemoryNumber {
-    fn eq(&self, other: &u16) -> bool {
-        self.0 == *other
+impl PartialEq<i64> for MemoryNumber {
+    fn eq(&self, other: &i64) -> bool {
+        self.signed() == i128::from(*other)
     }
 }
 
-impl PartialOrd<u16> for MemoryNumber {
-    fn partial_cmp(&self, other: &u16) -> Option<Ordering> {
-        self.0.partial_cmp(other)
+impl PartialOrd<i64> for MemoryNumber {
+    fn partial_cmp(&self, other: &i64) -> Option<Ordering> {
+        self.signed().partial_cmp(&i128::from(*other))
     }
 }
 
@@ -41,14 +100,18 @@ impl PartialOrd<u16> for MemoryNumber {
 impl AddAssign<MemoryDigit> for MemoryNumber {
     fn add_assign(&mut self, digit: MemoryDigit) {
         // Append digit to current memory value
-        let new_value = self.0 * 10 + u16::from(digit);
-        self.0 = new_value;
+        self.leading_zero |= self.digits > 0 && self.magnitude == 0;
+        self.magnitude = self
+            .magnitude
+            .saturating_mul(10)
+            .saturating_add(u64::from(digit));
+        self.digits = self.digits.saturating_add(1);
     }
 }
 
 // Memory checksum accumulation
-impl AddAssign<MemoryNumber> for u32 {
+impl AddAssign<MemoryNumber> for i128 {
     fn add_assign(&mut self, rhs: MemoryNumber) {
-        *self += rhs.0 as u32;
+        *self += rhs.signed();
     }
 }
@@ -1,19 +1,175 @@
 // memory/validation.rs
-use super::{MemoryNumber, MAX_MEMORY_VALUE};
+use super::MemoryNumber;
+use std::fmt;
 
-// Represents a validated 3-digit number from corrupted memory
+// Largest operand accepted by the original firmware
+const DEFAULT_MAX_VALUE: u32 = 999;
+
+// Widest operand accepted when limiting by digit count
+const MAX_DIGITS: u8 = 9;
+
+// Upper bound on operand size, either by value or by digit count
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OperandLimit {
+    Value(u32), // Largest accepted absolute value
+    Digits(u8), // Most digits accepted, leading zeros included
+}
+
+impl Default for OperandLimit {
+    fn default() -> Self {
+        OperandLimit::Value(DEFAULT_MAX_VALUE)
+    }
+}
+
+impl OperandLimit {
+    // Digit limits must be 1..=9 so operands always fit in a u32
+    pub fn digits(count: u8) -> Result<Self, OperandLimitError> {
+        if (1..=MAX_DIGITS).contains(&count) {
+            Ok(OperandLimit::Digits(count))
+        } else {
+            Err(OperandLimitError::Digits(count))
+        }
+    }
+
+    // Check whether a memory number stays within this limit
+    pub fn admits(&self, number: &MemoryNumber) -> bool {
+        match *self {
+            OperandLimit::Value(max) => number.magnitude() <= u64::from(max),
+            OperandLimit::Digits(max) => number.digits() <= max,
+        }
+    }
+}
+
+impl fmt::Display for OperandLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperandLimit::Value(max) => write!(f, "at most {}", max),
+            OperandLimit::Digits(max) => write!(f, "at most {} digits", max),
+        }
+    }
+}
+
+// Reasons an operand limit cannot be configured
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OperandLimitError {
+    Digits(u8), // Digit count outside 1..=9
+}
+
+impl fmt::Display for OperandLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperandLimitError::Digits(count) => write!(
+                f,
+                "operands cannot be limited to {} digits, expected 1 to {}",
+                count, MAX_DIGITS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OperandLimitError {}
+
+// Reasons a memory number fails validation
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryValueError {
+    // Operand is larger than the firmware allows
+    OutOfRange {
+        value: i64,
+        digits: u8,
+        limit: OperandLimit,
+    },
+    // Operand was written with a leading zero, like "007"
+    LeadingZero {
+        value: i64,
+        digits: u8,
+    },
+    // Operand has too many digits to fit in an i64
+    Overflow {
+        digits: u8,
+        negative: bool,
+    },
+}
+
+impl fmt::Display for MemoryValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryValueError::OutOfRange {
+                value,
+                digits,
+                limit,
+            } => write!(
+                f,
+                "memory value {} ({} digits) is out of range, expected {}",
+                value, digits, limit
+            ),
+            MemoryValueError::LeadingZero { value, digits } => write!(
+                f,
+                "memory value {} was written with leading zeros ({} digits)",
+                value, digits
+            ),
+            MemoryValueError::Overflow { digits, negative } => write!(
+                f,
+                "{} memory value with {} digits does not fit in 64 bits",
+                if *negative { "negative" } else { "positive" },
+                digits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MemoryValueError {}
+
+impl MemoryValueError {
+    // Report a memory number whose value does not fit in an i64
+    pub(crate) fn overflow(number: &MemoryNumber) -> Self {
+        MemoryValueError::Overflow {
+            digits: number.digits(),
+            negative: number.is_negative(),
+        }
+    }
+}
+
+// Represents a memory number that passed validation
 #[derive(Copy, Clone, Debug)]
-pub struct ValidMemoryValue;
+pub struct ValidMemoryValue(MemoryNumber);
 
-// Memory validation implementation
+impl ValidMemoryValue {
+    // Validate a memory number against an explicit operand limit
+    pub fn within(number: MemoryNumber, limit: OperandLimit) -> Result<Self, MemoryValueError> {
+        let value = number.value().ok_or(MemoryValueError::overflow(&number))?;
+        if limit.admits(&number) {
+            Ok(ValidMemoryValue(number))
+        } else {
+            Err(MemoryValueError::OutOfRange {
+                value,
+                digits: number.digits(),
+                limit,
+            })
+        }
+    }
+
+    pub fn number(&self) -> MemoryNumber {
+        self.0
+    }
+
+    pub fn value(&self) -> i64 {
+        self.0
+            .value()
+            .expect("validated memory values fit in an i64")
+    }
+}
+
+impl From<ValidMemoryValue> for MemoryNumber {
+    fn from(valid: ValidMemoryValue) -> MemoryNumber {
+        valid.0
+    }
+}
+
+// Memory validation against the original firmware limit
 impl TryFrom<MemoryNumber> for ValidMemoryValue {
-    type Error = ();
+    type Error = MemoryValueError;
 
     fn try_from(number: MemoryNumber) -> Result<Self, Self::Error> {
-        if number <= MAX_MEMORY_VALUE {
-            Ok(ValidMemoryValue)
-        } else {
-            Err(()) // Memory value exceeds valid range
-        }
+        ValidMemoryValue::within(number, OperandLimit::default())
     }
 }
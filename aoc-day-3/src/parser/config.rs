@@ -0,0 +1,95 @@
+// parser/config.rs
+use crate::memory::{
+    MemoryNumber, MemoryValueError, OperandLimit, OperandLimitError, ValidMemoryValue,
+};
+
+// Which instructions contribute to the reported checksum
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ParserMode {
+    Unconditional, // Every valid mul() counts, do()/don't() are ignored
+    #[default]
+    Conditional, // Only mul() while enabled by do()/don't() counts
+}
+
+// Operand encoding rules for a memory firmware revision
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParserConfig {
+    pub(crate) limit: OperandLimit, // Largest operand accepted
+    pub(crate) leading_zeros: bool, // Accept operands like "007"
+    pub(crate) signed: bool,        // Accept operands like "-12"
+    pub(crate) start_enabled: bool, // Initial do()/don't() state
+    pub(crate) mode: ParserMode,    // Checksum reported by MemoryParser::checksum
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        ParserConfig {
+            limit: OperandLimit::default(),
+            leading_zeros: true,
+            signed: false,
+            start_enabled: true, // Multiplications start enabled per specs
+            mode: ParserMode::default(),
+        }
+    }
+}
+
+impl ParserConfig {
+    // Limit operands by absolute value, products of any two always fit the
+    // i128 checksum registers
+    pub fn max_value(mut self, max: u32) -> Self {
+        self.limit = OperandLimit::Value(max);
+        self
+    }
+
+    // Limit operands by digit count, rejecting counts outside 1 to 9 digits
+    pub fn max_digits(mut self, count: u8) -> Result<Self, OperandLimitError> {
+        self.limit = OperandLimit::digits(count)?;
+        Ok(self)
+    }
+
+    // Accept or reject operands written with leading zeros
+    pub fn allow_leading_zeros(mut self, allow: bool) -> Self {
+        self.leading_zeros = allow;
+        self
+    }
+
+    // Accept or reject operands prefixed with '-'
+    pub fn allow_signed(mut self, allow: bool) -> Self {
+        self.signed = allow;
+        self
+    }
+
+    // Choose whether multiplications are enabled before any do()/don't()
+    pub fn start_enabled(mut self, enabled: bool) -> Self {
+        self.start_enabled = enabled;
+        self
+    }
+
+    // Choose which checksum MemoryParser::checksum reports
+    pub fn mode(mut self, mode: ParserMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn parser_mode(&self) -> ParserMode {
+        self.mode
+    }
+
+    pub fn limit(&self) -> OperandLimit {
+        self.limit
+    }
+
+    // Check an operand against the configured encoding
+    pub fn validate(&self, number: MemoryNumber) -> Result<ValidMemoryValue, MemoryValueError> {
+        if !self.leading_zeros && number.has_leading_zero() {
+            return Err(match number.value() {
+                Some(value) => MemoryValueError::LeadingZero {
+                    value,
+                    digits: number.digits(),
+                },
+                None => MemoryValueError::overflow(&number),
+            });
+        }
+        ValidMemoryValue::within(number, self.limit)
+    }
+}
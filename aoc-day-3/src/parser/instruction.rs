@@ -0,0 +1,20 @@
+// parser/instruction.rs
+use std::fmt;
+
+// Instruction recognized in corrupted memory
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Mul { x: i64, y: i64 }, // mul(X,Y)
+    Do,                     // do()
+    Dont,                   // don't()
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Mul { x, y } => write!(f, "mul({},{})", x, y),
+            Instruction::Do => write!(f, "do()"),
+            Instruction::Dont => write!(f, "don't()"),
+        }
+    }
+}
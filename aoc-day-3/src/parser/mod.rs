@@ -1,6 +1,12 @@
 // parser/mod.rs
+mod config;
+mod instruction;
+mod progress;
 mod scanner;
 mod state;
 
-pub use scanner::MemoryParser;
+pub use config::{ParserConfig, ParserMode};
+pub use instruction::Instruction;
+pub use progress::ScanProgress;
+pub use scanner::{Checksums, MemoryParser};
 pub use state::ParserState;
@@ -0,0 +1,35 @@
+// parser/progress.rs
+use std::fmt;
+
+// Snapshot of a long-running scan
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub bytes_scanned: usize, // Bytes handed to the parser so far
+    pub total_bytes: usize,   // Size of the whole dump
+    pub instructions: usize,  // Instructions recognized so far
+}
+
+impl ScanProgress {
+    pub fn is_complete(&self) -> bool {
+        self.bytes_scanned == self.total_bytes
+    }
+}
+
+impl fmt::Display for ScanProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const MIB: f64 = 1024.0 * 1024.0;
+        let percent = if self.total_bytes == 0 {
+            100.0
+        } else {
+            self.bytes_scanned as f64 * 100.0 / self.total_bytes as f64
+        };
+        write!(
+            f,
+            "{:.1} / {:.1} MiB scanned ({:.1}%), {} instructions found",
+            self.bytes_scanned as f64 / MIB,
+            self.total_bytes as f64 / MIB,
+            percent,
+            self.instructions
+        )
+    }
+}
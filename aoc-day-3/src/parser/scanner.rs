@@ -1,30 +1,146 @@
 // parser/scanner.rs
+use super::config::{ParserConfig, ParserMode};
+use super::instruction::Instruction;
+use super::progress::ScanProgress;
 use super::state::ParserState;
-use crate::memory::{MemoryDigit, ValidMemoryValue};
+use crate::memory::{MemoryDigit, MemoryNumber};
+use memchr::memchr2;
+use std::time::{Duration, Instant};
+
+// Bytes scanned between progress checks
+const PROGRESS_WINDOW: usize = 1 << 20;
+
+// Both checksums gathered during a single scan. Products of u32 operands fit
+// in 64 bits, so an i128 register cannot overflow on any dump that fits in memory
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checksums {
+    pub unconditional: i128, // Every valid multiplication
+    pub conditional: i128,   // Multiplications enabled by do()/don't()
+}
+
+impl Checksums {
+    pub fn get(&self, mode: ParserMode) -> &i128 {
+        match mode {
+            ParserMode::Unconditional => &self.unconditional,
+            ParserMode::Conditional => &self.conditional,
+        }
+    }
+}
 
 // Parser for scanning corrupted memory
 pub struct MemoryParser {
-    checksum: u32,      // Running total of valid multiplication results
-    state: ParserState, // Current parser state for memory validation
-    mul_enabled: bool,  // Tracks if multiplication is enabled by do()/don't()
+    checksums: Checksums, // Running totals of valid multiplication results
+    state: ParserState,   // Current parser state for memory validation
+    mul_enabled: bool,    // Tracks if multiplication is enabled by do()/don't()
+    config: ParserConfig, // Operand encoding rules for this firmware revision
 }
 
 impl Default for MemoryParser {
     fn default() -> Self {
+        MemoryParser::with_config(ParserConfig::default())
+    }
+}
+
+impl MemoryParser {
+    pub fn with_config(config: ParserConfig) -> Self {
         MemoryParser {
-            checksum: 0,
+            checksums: Checksums::default(),
             state: ParserState::Initial,
-            mul_enabled: true, // Multiplications start enabled per specs
+            mul_enabled: config.start_enabled,
+            config,
         }
     }
-}
 
-impl MemoryParser {
-    pub fn checksum(&self) -> &u32 {
-        &self.checksum
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
     }
-    pub fn process_char(&mut self, c: char) {
+
+    // Checksum selected by the configured ParserMode
+    pub fn checksum(&self) -> &i128 {
+        self.checksums.get(self.config.mode)
+    }
+
+    pub fn checksums(&self) -> Checksums {
+        self.checksums
+    }
+
+    // Whether mul() currently counts towards the conditional checksum
+    pub fn mul_enabled(&self) -> bool {
+        self.mul_enabled
+    }
+
+    // Enter an operand state if the first digit already fits the config
+    fn operand_start(
+        &self,
+        number: MemoryNumber,
+        next: impl FnOnce(MemoryNumber) -> ParserState,
+    ) -> ParserState {
+        match self.config.validate(number) {
+            Ok(valid) => next(valid.into()),
+            Err(_) => ParserState::Initial,
+        }
+    }
+
+    // Scan a chunk of corrupted memory, skipping noise between instructions
+    pub fn process_bytes(&mut self, bytes: &[u8]) {
+        self.process_bytes_with(bytes, |_, _| {});
+    }
+
+    // Scan a chunk of corrupted memory, reporting each instruction with its
+    // offset into `bytes`
+    pub fn process_bytes_with(
+        &mut self,
+        bytes: &[u8],
+        mut on_instruction: impl FnMut(usize, Instruction),
+    ) {
+        let mut offset = 0;
+        while offset < bytes.len() {
+            // Only 'm' and 'd' can leave the initial state, so jump straight to them
+            if matches!(self.state, ParserState::Initial) {
+                match memchr2(b'm', b'd', &bytes[offset..]) {
+                    Some(skip) => offset += skip,
+                    None => return,
+                }
+            }
+
+            if let Some(instruction) = self.process_char(bytes[offset] as char) {
+                on_instruction(offset, instruction);
+            }
+            offset += 1;
+        }
+    }
+
+    // Scan a large slice such as a memory-mapped dump, reporting progress at
+    // most once per `interval` and always once at the end
+    pub fn process_bytes_with_progress(
+        &mut self,
+        bytes: &[u8],
+        interval: Duration,
+        mut report: impl FnMut(&ScanProgress),
+    ) {
+        let mut progress = ScanProgress {
+            total_bytes: bytes.len(),
+            ..ScanProgress::default()
+        };
+        let mut last_report = Instant::now();
+
+        for window in bytes.chunks(PROGRESS_WINDOW) {
+            self.process_bytes_with(window, |_, _| progress.instructions += 1);
+            progress.bytes_scanned += window.len();
+
+            if last_report.elapsed() >= interval && !progress.is_complete() {
+                report(&progress);
+                last_report = Instant::now();
+            }
+        }
+
+        report(&progress);
+    }
+
+    // Advance the state machine, returning any instruction completed by `c`
+    pub fn process_char(&mut self, c: char) -> Option<Instruction> {
         use ParserState::*;
+        let mut recognized = None;
         match &mut self.state {
             Initial => {
                 if c == 'm' {
@@ -56,7 +172,16 @@ impl MemoryParser {
             }
             MulLParen => {
                 if let Ok(digit) = MemoryDigit::try_from(c) {
-                    self.state = ParsingX(digit.into());
+                    self.state = self.operand_start(digit.into(), ParsingX);
+                } else if c == '-' && self.config.signed {
+                    self.state = NegativeX;
+                } else {
+                    self.state = Initial;
+                }
+            }
+            NegativeX => {
+                if let Ok(digit) = MemoryDigit::try_from(c) {
+                    self.state = self.operand_start(-MemoryNumber::from(digit), ParsingX);
                 } else {
                     self.state = Initial;
                 }
@@ -66,7 +191,7 @@ impl MemoryParser {
                     *number += digit;
 
                     // Validate memory value constraints
-                    if ValidMemoryValue::try_from(*number).is_err() {
+                    if self.config.validate(*number).is_err() {
                         self.state = Initial;
                     }
                 } else if c == ',' {
@@ -77,7 +202,18 @@ impl MemoryParser {
             }
             AfterComma(x) => {
                 if let Ok(digit) = MemoryDigit::try_from(c) {
-                    self.state = ParsingY(*x, digit.into());
+                    let x = *x;
+                    self.state = self.operand_start(digit.into(), |y| ParsingY(x, y));
+                } else if c == '-' && self.config.signed {
+                    self.state = NegativeY(*x);
+                } else {
+                    self.state = Initial;
+                }
+            }
+            NegativeY(x) => {
+                if let Ok(digit) = MemoryDigit::try_from(c) {
+                    let x = *x;
+                    self.state = self.operand_start(-MemoryNumber::from(digit), |y| ParsingY(x, y));
                 } else {
                     self.state = Initial;
                 }
@@ -87,13 +223,19 @@ impl MemoryParser {
                     *y += digit;
 
                     // Validate memory value constraints
-                    if ValidMemoryValue::try_from(*y).is_err() {
+                    if self.config.validate(*y).is_err() {
                         self.state = Initial;
                     }
                 } else if c == ')' {
+                    // Both operands passed validation, so they fit in an i64
+                    recognized = Some(Instruction::Mul {
+                        x: x.value().expect("validated operand"),
+                        y: y.value().expect("validated operand"),
+                    });
+                    let result = x * y;
+                    self.checksums.unconditional += result;
                     if self.mul_enabled {
-                        let result = x * y;
-                        self.checksum += result;
+                        self.checksums.conditional += result;
                     }
                     self.state = Initial;
                 } else {
@@ -119,6 +261,7 @@ impl MemoryParser {
             DoLparen => {
                 if c == ')' {
                     self.mul_enabled = true; // Enable multiplication operations
+                    recognized = Some(Instruction::Do);
                     self.state = Initial;
                 } else {
                     self.state = Initial;
@@ -148,11 +291,13 @@ impl MemoryParser {
             DontLparen => {
                 if c == ')' {
                     self.mul_enabled = false; // Disable multiplication operations
+                    recognized = Some(Instruction::Dont);
                     self.state = Initial;
                 } else {
                     self.state = Initial;
                 }
             }
         }
+        recognized
     }
 }
@@ -8,8 +8,10 @@ pub enum ParserState {
     MU,
     Mul,
     MulLParen,
+    NegativeX,
     ParsingX(MemoryNumber),
     AfterComma(MemoryNumber),
+    NegativeY(MemoryNumber),
     ParsingY(MemoryNumber, MemoryNumber),
     D,
     DO,
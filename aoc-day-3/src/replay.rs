@@ -0,0 +1,291 @@
+// replay.rs
+use crate::parser::{Checksums, Instruction, MemoryParser, ParserConfig, ParserMode};
+use std::fmt;
+use std::io::{self, Write};
+
+// Where a replay stops executing
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StopAt {
+    #[default]
+    End, // Replay the whole dump
+    Offset(usize),       // Stop before this byte offset
+    Instructions(usize), // Stop after this many instructions
+}
+
+// One executed instruction in an execution trace
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    pub index: usize,             // Position in the instruction stream
+    pub offset: usize,            // Byte offset that completed the instruction
+    pub instruction: Instruction, // Instruction that was executed
+    pub enabled: bool,            // Enable state after execution
+    pub checksums: Checksums,     // Running checksums after execution
+    pub mode: ParserMode,         // Checksum shown as the running total
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>10}  #{:<8} {:<20} {:<8} {}",
+            self.offset,
+            self.index,
+            self.instruction.to_string(),
+            if self.enabled { "enabled" } else { "disabled" },
+            self.checksums.get(self.mode)
+        )
+    }
+}
+
+// Interpreter that replays the instruction stream of a memory dump
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Replay {
+    config: ParserConfig, // Firmware revision being replayed
+    stop: StopAt,         // Where the replay ends
+}
+
+impl Replay {
+    pub fn new(config: ParserConfig) -> Self {
+        Replay {
+            config,
+            stop: StopAt::End,
+        }
+    }
+
+    // Stop before the given byte offset
+    pub fn stop_at_offset(mut self, offset: usize) -> Self {
+        self.stop = StopAt::Offset(offset);
+        self
+    }
+
+    // Stop after the given number of instructions
+    pub fn stop_after(mut self, instructions: usize) -> Self {
+        self.stop = StopAt::Instructions(instructions);
+        self
+    }
+
+    // Execute the dump lazily, one step per recognized instruction
+    pub fn steps<'a>(&self, bytes: &'a [u8]) -> ReplaySteps<'a> {
+        let end = match self.stop {
+            StopAt::Offset(offset) => offset.min(bytes.len()),
+            _ => bytes.len(),
+        };
+        let max_steps = match self.stop {
+            StopAt::Instructions(count) => count,
+            _ => usize::MAX,
+        };
+
+        ReplaySteps {
+            parser: MemoryParser::with_config(self.config),
+            bytes: &bytes[..end],
+            offset: 0,
+            index: 0,
+            max_steps,
+        }
+    }
+
+    // Execute the dump, handing each step to `on_step`
+    pub fn run(&self, bytes: &[u8], mut on_step: impl FnMut(&TraceStep)) -> Checksums {
+        let mut steps = self.steps(bytes);
+        for step in steps.by_ref() {
+            on_step(&step);
+        }
+        steps.checksums()
+    }
+
+    // Collect every executed step
+    pub fn trace(&self, bytes: &[u8]) -> Vec<TraceStep> {
+        let mut steps = Vec::new();
+        self.run(bytes, |step| steps.push(*step));
+        steps
+    }
+
+    // Write a human-readable execution trace, one instruction per line
+    pub fn write_trace<W: Write>(&self, bytes: &[u8], mut writer: W) -> io::Result<Checksums> {
+        writeln!(
+            writer,
+            "{:>10}  {:<9} {:<20} {:<8} checksum",
+            "offset", "step", "instruction", "state"
+        )?;
+        let mut result = Ok(());
+        let checksums = self.run(bytes, |step| {
+            if result.is_ok() {
+                result = writeln!(writer, "{}", step);
+            }
+        });
+        result.map(|_| checksums)
+    }
+}
+
+// Steps of a replay in progress. The parser keeps the running checksums and
+// enable state, so every step reports exactly what a scan would
+pub struct ReplaySteps<'a> {
+    parser: MemoryParser, // Parser executing the dump
+    bytes: &'a [u8],      // Dump up to the stop offset
+    offset: usize,        // Next byte to execute
+    index: usize,         // Steps taken so far
+    max_steps: usize,     // Steps allowed before stopping
+}
+
+impl ReplaySteps<'_> {
+    // Checksums after the steps taken so far
+    pub fn checksums(&self) -> Checksums {
+        self.parser.checksums()
+    }
+}
+
+impl Iterator for ReplaySteps<'_> {
+    type Item = TraceStep;
+
+    fn next(&mut self) -> Option<TraceStep> {
+        while self.index < self.max_steps && self.offset < self.bytes.len() {
+            let offset = self.offset;
+            self.offset += 1;
+            let Some(instruction) = self.parser.process_char(self.bytes[offset] as char) else {
+                continue;
+            };
+
+            let step = TraceStep {
+                index: self.index,
+                offset,
+                instruction,
+                enabled: self.parser.mul_enabled(),
+                checksums: self.parser.checksums(),
+                mode: self.parser.config().parser_mode(),
+            };
+            self.index += 1;
+            return Some(step);
+        }
+        None
+    }
+}
+
+// First point where two firmware revisions disagree about a dump
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,             // Position in the instruction stream
+    pub left: Option<TraceStep>,  // Step taken by the left revision, if any
+    pub right: Option<TraceStep>, // Step taken by the right revision, if any
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Instruction streams diverge at step #{}", self.index)?;
+        for (side, step) in [("left", self.left), ("right", self.right)] {
+            match step {
+                Some(step) => writeln!(f, "  {:<5} {}", side, step)?,
+                None => writeln!(f, "  {:<5} <end of stream>", side)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Replay a dump under two firmware revisions in lockstep and find the first
+// step where they disagree on the instruction, its offset, the enable state or
+// the checksum. Replay stops at the first disagreement
+pub fn find_divergence(
+    bytes: &[u8],
+    left: ParserConfig,
+    right: ParserConfig,
+) -> Option<Divergence> {
+    let mut left_steps = Replay::new(left).steps(bytes);
+    let mut right_steps = Replay::new(right).steps(bytes);
+
+    let mut index = 0;
+    loop {
+        match (left_steps.next(), right_steps.next()) {
+            (None, None) => return None,
+            (left, right) if left != right => return Some(Divergence { index, left, right }),
+            _ => index += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONDITIONAL_EXAMPLE: &[u8] =
+        b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)do()?mul(8,5))";
+
+    #[test]
+    fn test_trace_steps() {
+        let steps = Replay::default().trace(CONDITIONAL_EXAMPLE);
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[0].offset, 8);
+        assert_eq!(steps[0].checksums.conditional, 8);
+        assert_eq!(steps[1].instruction, Instruction::Dont);
+        assert!(!steps[2].enabled);
+        assert_eq!(steps[2].checksums.conditional, 8);
+        assert_eq!(steps[2].checksums.unconditional, 33);
+
+        let last = steps.last().unwrap();
+        assert_eq!(last.checksums.conditional, 48);
+        assert_eq!(last.checksums.unconditional, 161);
+
+        // Steps are executed lazily, with the scanner's own running checksums
+        let mut lazy = Replay::default().steps(CONDITIONAL_EXAMPLE);
+        assert_eq!(lazy.next(), Some(steps[0]));
+        assert_eq!(lazy.checksums(), steps[0].checksums);
+        assert_eq!(lazy.collect::<Vec<_>>(), steps[1..]);
+    }
+
+    #[test]
+    fn test_trace_shows_configured_checksum() {
+        let unconditional = ParserConfig::default().mode(ParserMode::Unconditional);
+        let mut output = Vec::new();
+        Replay::new(unconditional)
+            .write_trace(CONDITIONAL_EXAMPLE, &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let totals: Vec<_> = output
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().last().unwrap())
+            .collect();
+        assert_eq!(totals, ["8", "8", "33", "121", "121", "161"]);
+
+        let steps = Replay::default().trace(CONDITIONAL_EXAMPLE);
+        assert!(steps[5].to_string().ends_with(" 48"));
+    }
+
+    #[test]
+    fn test_stop_conditions() {
+        let replay = Replay::default().stop_after(2);
+        assert_eq!(replay.trace(CONDITIONAL_EXAMPLE).len(), 2);
+
+        // The first instruction completes at offset 8, so stopping there skips it
+        assert!(Replay::default()
+            .stop_at_offset(8)
+            .trace(CONDITIONAL_EXAMPLE)
+            .is_empty());
+        assert_eq!(
+            Replay::default()
+                .stop_at_offset(9)
+                .run(CONDITIONAL_EXAMPLE, |_| {}),
+            Checksums {
+                unconditional: 8,
+                conditional: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_divergence() {
+        let dump = b"mul(2,3)mul(1000,2)mul(4,5)";
+        let wide = ParserConfig::default().max_digits(4).unwrap();
+        assert_eq!(find_divergence(dump, wide, wide), None);
+
+        let divergence = find_divergence(dump, ParserConfig::default(), wide).unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(
+            divergence.left.unwrap().instruction,
+            Instruction::Mul { x: 4, y: 5 }
+        );
+        assert_eq!(
+            divergence.right.unwrap().instruction,
+            Instruction::Mul { x: 1000, y: 2 }
+        );
+    }
+}
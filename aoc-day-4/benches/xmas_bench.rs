@@ -1,4 +1,4 @@
-use aoc_day_4::Grid;
+use aoc_day_4::{CrossSearchPath, Dictionary, Grid, StreamingSearch, DEFAULT_BAND_ROWS};
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::{fs, str::FromStr, time::Duration};
 
@@ -17,8 +17,81 @@ fn bench_xmas_search(c: &mut Criterion) {
         b.iter(|| criterion::black_box(grid.find_crossed_mas()))
     });
 
+    for path in CrossSearchPath::ALL {
+        if path.is_supported() {
+            let name = format!("find_crossed_mas_{:?}", path).to_lowercase();
+            group.bench_function(name, |b| {
+                b.iter(|| criterion::black_box(grid.find_crossed_mas_with(path)))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_dictionary_search(c: &mut Criterion) {
+    let input = fs::read_to_string("part_one.input").expect("Failed to read input file");
+    let grid = Grid::from_str(&input).unwrap();
+
+    // Every four-letter word over the puzzle's alphabet
+    let letters = ['X', 'M', 'A', 'S'];
+    let words: Vec<String> = (0..256)
+        .map(|n: usize| (0..4).map(|i| letters[(n >> (2 * i)) & 3]).collect())
+        .collect();
+    let dictionary = Dictionary::new(&words).unwrap();
+
+    let mut group = c.benchmark_group("dictionary");
+    group.measurement_time(Duration::from_secs(10));
+
+    group.bench_function("find_words", |b| {
+        b.iter(|| criterion::black_box(grid.find_words(&dictionary).total()))
+    });
+
+    group.bench_function("find_word_per_word", |b| {
+        b.iter(|| criterion::black_box(words.iter().map(|w| grid.count_word(w)).sum::<usize>()))
+    });
+
+    group.finish();
+}
+
+fn bench_banded_search(c: &mut Criterion) {
+    let input = fs::read_to_string("part_one.input").expect("Failed to read input file");
+
+    // Tile the puzzle into a grid sixteen times as tall and four times as wide
+    let wide: String = input.lines().map(|row| row.repeat(4) + "\n").collect();
+    let tall = wide.repeat(16);
+    let grid = Grid::from_str(&tall).unwrap();
+
+    let mut group = c.benchmark_group("bands");
+    group.measurement_time(Duration::from_secs(10));
+
+    group.bench_function("count_word", |b| {
+        b.iter(|| criterion::black_box(grid.count_word("XMAS")))
+    });
+
+    group.bench_function("par_count_word", |b| {
+        b.iter(|| criterion::black_box(grid.par_count_word("XMAS", DEFAULT_BAND_ROWS)))
+    });
+
+    group.bench_function("find_crossed_mas", |b| {
+        b.iter(|| criterion::black_box(grid.find_crossed_mas()))
+    });
+
+    group.bench_function("par_find_crossed_mas", |b| {
+        b.iter(|| criterion::black_box(grid.par_find_crossed_mas(DEFAULT_BAND_ROWS)))
+    });
+
+    group.bench_function("streaming_search", |b| {
+        b.iter(|| criterion::black_box(StreamingSearch::new().search(tall.as_bytes()).unwrap()))
+    });
+
     group.finish();
 }
 
-criterion_group!(benches, bench_xmas_search);
+criterion_group!(
+    benches,
+    bench_xmas_search,
+    bench_dictionary_search,
+    bench_banded_search
+);
 criterion_main!(benches);
@@ -0,0 +1,265 @@
+//! Searching very large grids in horizontal bands of rows.
+//!
+//! Every match is owned by the band holding its topmost row. A band also reads a few
+//! rows past its end, so a match that starts near the bottom of one band is still seen
+//! whole, yet counted by that band alone. Bands can then be searched in parallel, or
+//! read one at a time from a stream so that only a bounded window of rows is in memory.
+
+use crate::{check_row, CrossSearchPath, Grid, ParseGridError};
+use rayon::prelude::*;
+use std::io::{self, BufRead};
+
+/// Rows shared between neighbouring bands: a vertical "XMAS" spans four rows.
+pub const BAND_OVERLAP: usize = 3;
+
+/// Rows owned by each band unless a caller asks otherwise.
+pub const DEFAULT_BAND_ROWS: usize = 512;
+
+impl Grid {
+    /// Count the occurrences of `word` with rayon, one task per band of `band_rows` rows.
+    /// Results agree with [`Grid::count_word`] for any band size.
+    /// The Elf hands each helper a strip of the puzzle, with a little overlap to spare.
+    pub fn par_count_word(&self, word: &str, band_rows: usize) -> usize {
+        self.par_bands(band_rows)
+            .map(|rows| self.find_word_in_rows(word, rows).len())
+            .sum()
+    }
+
+    /// Count the "X-MAS" crosses with rayon, one task per band of `band_rows` rows.
+    /// Results agree with [`Grid::find_crossed_mas`] for any band size.
+    pub fn par_find_crossed_mas(&self, band_rows: usize) -> usize {
+        let path = CrossSearchPath::detect();
+        self.par_bands(band_rows)
+            .map(|rows| self.crossed_mas_in_rows(path, rows))
+            .sum()
+    }
+
+    /// The rows owned by each band, in parallel.
+    fn par_bands(
+        &self,
+        band_rows: usize,
+    ) -> impl ParallelIterator<Item = std::ops::Range<usize>> + '_ {
+        let band_rows = band_rows.max(1);
+        (0..self.height.div_ceil(band_rows))
+            .into_par_iter()
+            .map(move |band| band * band_rows..((band + 1) * band_rows).min(self.height))
+    }
+}
+
+/// Totals from a streaming search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamCounts {
+    /// Rows read from the stream.
+    pub rows: usize,
+    /// Occurrences of the searched word.
+    pub words: usize,
+    /// "X-MAS" crosses.
+    pub crosses: usize,
+}
+
+/// Searches a grid read row by row, keeping only one band and its overlap in memory.
+/// The Elf feeds the puzzle through her reading frame, one strip at a time.
+#[derive(Debug, Clone)]
+pub struct StreamingSearch {
+    word: String,
+    band_rows: usize,
+}
+
+impl Default for StreamingSearch {
+    fn default() -> Self {
+        StreamingSearch::new()
+    }
+}
+
+impl StreamingSearch {
+    /// Search for "XMAS" and "X-MAS" crosses, [`DEFAULT_BAND_ROWS`] rows at a time.
+    pub fn new() -> Self {
+        StreamingSearch {
+            word: "XMAS".to_string(),
+            band_rows: DEFAULT_BAND_ROWS,
+        }
+    }
+
+    /// Search for `word` instead of "XMAS".
+    pub fn word(mut self, word: &str) -> Self {
+        self.word = word.to_string();
+        self
+    }
+
+    /// Rows searched per band, at least one.
+    pub fn band_rows(mut self, rows: usize) -> Self {
+        self.band_rows = rows.max(1);
+        self
+    }
+
+    /// Rows carried over from one band to the next: [`BAND_OVERLAP`], or more for
+    /// words longer than four letters.
+    pub fn overlap(&self) -> usize {
+        BAND_OVERLAP.max(self.word.len().saturating_sub(1))
+    }
+
+    /// Read the grid from `reader` and count its matches. At most
+    /// `band_rows + overlap()` rows are held at once, whatever the grid's height.
+    /// Rows are read as in [`ParseMode::Lenient`](crate::ParseMode::Lenient), and a
+    /// malformed row fails with [`io::ErrorKind::InvalidData`] wrapping a [`ParseGridError`].
+    pub fn search<R: BufRead>(&self, mut reader: R) -> io::Result<StreamCounts> {
+        let path = CrossSearchPath::detect();
+        let window_rows = self.band_rows + self.overlap();
+        let mut counts = StreamCounts::default();
+        let mut width = None;
+        let mut window = Vec::new();
+        let mut window_height = 0;
+        let mut blank_row = None;
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            let row = line.strip_suffix(b"\n").unwrap_or(&line);
+            let row = row.strip_suffix(b"\r").unwrap_or(row);
+
+            // Every row must be as wide as the first, just like a parsed grid.
+            // Blank lines are only allowed after the last row.
+            let width = *width.get_or_insert(row.len());
+            let invalid = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+            if width == 0 {
+                return Err(invalid(ParseGridError::Empty));
+            }
+            if row.is_empty() {
+                blank_row.get_or_insert(counts.rows);
+                continue;
+            }
+            if let Some(blank_row) = blank_row {
+                return Err(invalid(ParseGridError::RaggedRow {
+                    row: blank_row,
+                    expected: width,
+                    actual: 0,
+                }));
+            }
+            check_row(counts.rows, row, width).map_err(invalid)?;
+
+            window.extend_from_slice(row);
+            window_height += 1;
+            counts.rows += 1;
+
+            if window_height == window_rows {
+                window = self.search_band(window, width, self.band_rows, path, &mut counts);
+                window_height -= self.band_rows;
+            }
+        }
+
+        let Some(width) = width else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                ParseGridError::Empty,
+            ));
+        };
+        self.search_band(window, width, window_height, path, &mut counts);
+        Ok(counts)
+    }
+
+    /// Count the matches owned by the first `owned` rows of the window, then drop
+    /// those rows and hand back the overlap for the next band.
+    fn search_band(
+        &self,
+        window: Vec<u8>,
+        width: usize,
+        owned: usize,
+        path: CrossSearchPath,
+        counts: &mut StreamCounts,
+    ) -> Vec<u8> {
+        let grid = Grid::from_cells(window, width);
+        counts.words += grid.find_word_in_rows(&self.word, 0..owned).len();
+        counts.crosses += grid.crossed_mas_in_rows(path, 0..owned);
+
+        let mut window = grid.cells;
+        window.drain(..owned * width);
+        window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::letter_soup;
+
+    #[test]
+    fn test_parallel_bands_agree() {
+        let grid = letter_soup(97, 41, 11);
+        let words = grid.count_word("XMAS");
+        let crosses = grid.find_crossed_mas();
+        assert!(words > 0 && crosses > 0);
+
+        // Bands smaller than, equal to and larger than a word, and one band overall.
+        for band_rows in [0, 1, 2, 3, 4, 5, 7, 96, 97, 500] {
+            assert_eq!(grid.par_count_word("XMAS", band_rows), words);
+            assert_eq!(
+                grid.par_count_word("MAS", band_rows),
+                grid.count_word("MAS")
+            );
+            assert_eq!(grid.par_find_crossed_mas(band_rows), crosses);
+        }
+    }
+
+    #[test]
+    fn test_streaming_agrees() {
+        let grid = letter_soup(64, 23, 5);
+        let text = grid.to_string();
+
+        for band_rows in [1, 2, 3, 4, 10, 63, 64, 100] {
+            let counts = StreamingSearch::new()
+                .band_rows(band_rows)
+                .search(text.as_bytes())
+                .unwrap();
+            assert_eq!(
+                counts,
+                StreamCounts {
+                    rows: 64,
+                    words: grid.count_word("XMAS"),
+                    crosses: grid.find_crossed_mas(),
+                },
+                "{band_rows} rows per band"
+            );
+        }
+
+        let long = StreamingSearch::new().word("XMASXM").band_rows(2);
+        assert_eq!(long.overlap(), 5);
+        assert_eq!(
+            long.search(text.as_bytes()).unwrap().words,
+            grid.count_word("XMASXM")
+        );
+
+        let crlf = text.replace('\n', "\r\n");
+        assert_eq!(
+            StreamingSearch::new()
+                .search(crlf.as_bytes())
+                .unwrap()
+                .words,
+            grid.count_word("XMAS")
+        );
+    }
+
+    #[test]
+    fn test_streaming_rejects_ragged_rows() {
+        let error = StreamingSearch::new()
+            .search("XMAS\nXMA\n".as_bytes())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.into_inner().unwrap().downcast_ref::<ParseGridError>(),
+            Some(&ParseGridError::RaggedRow {
+                row: 1,
+                expected: 4,
+                actual: 3,
+            })
+        );
+
+        // Blank lines may trail the grid, but not split it.
+        let search = StreamingSearch::new();
+        assert_eq!(search.search("XMAS\n\n\n".as_bytes()).unwrap().rows, 1);
+        assert!(search.search("XMAS\n\nXMAS\n".as_bytes()).is_err());
+        assert!(search.search(&b""[..]).is_err());
+    }
+}
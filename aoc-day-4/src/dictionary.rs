@@ -0,0 +1,175 @@
+//! Multi-word search over a [`Grid`].
+//!
+//! The Elf's puzzle authors keep dictionaries of tens of thousands of words. Rather than
+//! searching for each word on its own, the whole dictionary is compiled into one
+//! Aho-Corasick automaton, and every ray of the grid is fed through it exactly once.
+
+use crate::{Direction, Grid, WordMatch};
+use aho_corasick::{AhoCorasick, BuildError};
+use std::collections::HashMap;
+
+/// A compiled set of words to search for in a single pass.
+/// The Elf pins the whole word list to the wall before the search begins.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    automaton: AhoCorasick,
+    words: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl Dictionary {
+    /// Compile a dictionary, ignoring empty and duplicate words.
+    pub fn new<I, S>(words: I) -> Result<Self, BuildError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut index = HashMap::new();
+        let mut unique = Vec::new();
+        for word in words {
+            let word = word.as_ref();
+            if !word.is_empty() && !index.contains_key(word) {
+                index.insert(word.to_string(), unique.len());
+                unique.push(word.to_string());
+            }
+        }
+
+        Ok(Dictionary {
+            automaton: AhoCorasick::new(&unique)?,
+            words: unique,
+            index,
+        })
+    }
+
+    /// The distinct words in the dictionary, in insertion order.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Matches for every word of a [`Dictionary`], in dictionary order.
+#[derive(Debug, Clone)]
+pub struct DictionaryMatches<'d> {
+    dictionary: &'d Dictionary,
+    matches: Vec<Vec<WordMatch>>,
+}
+
+impl<'d> DictionaryMatches<'d> {
+    /// Matches for one word, or `None` if it is not in the dictionary.
+    pub fn get(&self, word: &str) -> Option<&[WordMatch]> {
+        let &index = self.dictionary.index.get(word)?;
+        Some(&self.matches[index])
+    }
+
+    /// Every word paired with its matches, including words that were not found.
+    pub fn iter(&self) -> impl Iterator<Item = (&'d str, &[WordMatch])> + '_ {
+        self.dictionary
+            .words
+            .iter()
+            .map(String::as_str)
+            .zip(self.matches.iter().map(Vec::as_slice))
+    }
+
+    /// Total number of matches across all words.
+    pub fn total(&self) -> usize {
+        self.matches.iter().map(Vec::len).sum()
+    }
+}
+
+impl Grid {
+    /// Find every dictionary word in the grid, walking each of the eight
+    /// directions' rays once. Results agree with calling [`Grid::find_word`] per word.
+    /// The Elf reads each line of the puzzle once, ticking off every word it contains.
+    pub fn find_words<'d>(&self, dictionary: &'d Dictionary) -> DictionaryMatches<'d> {
+        let mut matches = vec![Vec::new(); dictionary.len()];
+        let mut ray = Vec::with_capacity(self.height.max(self.width));
+
+        for dir in Direction::ALL {
+            for line in self.rays(dir) {
+                // Gather the letters along this ray.
+                let (start_row, start_col) = line.start();
+                ray.clear();
+                ray.extend(line);
+
+                for found in dictionary.automaton.find_overlapping_iter(&ray) {
+                    let index = found.pattern().as_usize();
+                    let len = found.len();
+
+                    // A single letter reads the same in every direction, count it once.
+                    if len == 1 && dir != Direction::ALL[0] {
+                        continue;
+                    }
+
+                    let offset = found.start() as isize;
+                    matches[index].push(WordMatch {
+                        row: (start_row as isize + dir.dy as isize * offset) as usize,
+                        col: (start_col as isize + dir.dx as isize * offset) as usize,
+                        direction: dir,
+                        len,
+                    });
+                }
+            }
+        }
+
+        DictionaryMatches {
+            dictionary,
+            matches,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "MMMSXXMASM\n\
+                          MSAMXMSMSA\n\
+                          AMXSXMAAMM\n\
+                          MSAMASMSMX\n\
+                          XMASAMXAMM\n\
+                          XXAMMXXAMA\n\
+                          SMSMSASXSS\n\
+                          SAXAMASAAA\n\
+                          MAMMMXMMMM\n\
+                          MXMXAXMASX";
+
+    #[test]
+    fn test_dictionary_agrees_with_find_word() {
+        let grid: Grid = PUZZLE.parse().unwrap();
+        let words = [
+            "XMAS",
+            "SAMX",
+            "MAS",
+            "AM",
+            "S",
+            "MMMSXXMASM",
+            "XMASX",
+            "QQ",
+            "XMAS",
+            "",
+        ];
+        let dictionary = Dictionary::new(words).unwrap();
+        assert_eq!(dictionary.len(), 8);
+
+        let found = grid.find_words(&dictionary);
+        for word in dictionary.words() {
+            let mut expected = grid.find_word(word);
+            let mut actual = found.get(word).unwrap().to_vec();
+            expected.sort_by_key(|m| (m.row, m.col, m.direction.dy(), m.direction.dx()));
+            actual.sort_by_key(|m| (m.row, m.col, m.direction.dy(), m.direction.dx()));
+            assert_eq!(actual, expected, "matches for {word:?}");
+        }
+
+        assert_eq!(found.get("XMAS").unwrap().len(), 18);
+        assert!(found.get("QQ").unwrap().is_empty());
+        assert!(found.get("missing").is_none());
+    }
+}
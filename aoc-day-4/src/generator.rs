@@ -0,0 +1,504 @@
+//! Word search puzzles built to order.
+//!
+//! Words are placed at random along the allowed directions, sharing a limited number
+//! of cells with each other. The remaining cells are filled with distractor letters,
+//! each chosen so that it never completes a word nobody placed. The answer key is
+//! therefore exactly what [`Grid::find_word`] reports for every word.
+
+use crate::{find_word_in, Direction, Grid, WordMatch};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+// Marks a cell that holds no letter yet, never a letter of a word.
+const EMPTY: u8 = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratePuzzleError {
+    /// A word is empty or holds something other than printable ASCII.
+    InvalidWord(String),
+    /// A word is too long for the grid in every allowed direction.
+    DoesNotFit(String),
+    /// The alphabet is empty or holds something other than printable ASCII.
+    InvalidAlphabet(String),
+    /// No direction was allowed, so no word can be placed.
+    NoDirections,
+    /// No attempt placed every word without creating an unintended match.
+    Exhausted { attempts: usize },
+}
+
+impl fmt::Display for GeneratePuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratePuzzleError::InvalidWord(word) => {
+                write!(f, "{:?} cannot be hidden in a grid", word)
+            }
+            GeneratePuzzleError::DoesNotFit(word) => {
+                write!(f, "{:?} does not fit in the grid", word)
+            }
+            GeneratePuzzleError::InvalidAlphabet(letters) => {
+                write!(f, "{:?} cannot fill the cells of a grid", letters)
+            }
+            GeneratePuzzleError::NoDirections => {
+                write!(
+                    f,
+                    "No directions allowed - the Elf cannot place a single word"
+                )
+            }
+            GeneratePuzzleError::Exhausted { attempts } => write!(
+                f,
+                "Gave up after {} attempts - the Elf cannot hide these words without extra matches",
+                attempts
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GeneratePuzzleError {}
+
+/// Settings for a generated word search.
+/// The Elf sets out a blank grid and a list of words to hide in it.
+#[derive(Debug, Clone)]
+pub struct PuzzleGenerator {
+    height: usize,
+    width: usize,
+    words: Vec<String>,
+    directions: Vec<Direction>,
+    seed: u64,
+    max_overlap: usize,
+    alphabet: Option<String>,
+    attempts: usize,
+}
+
+impl PuzzleGenerator {
+    /// A generator for a `height` by `width` grid, placing words in all eight
+    /// directions with at most one shared cell between crossing words.
+    pub fn new(height: usize, width: usize) -> Self {
+        PuzzleGenerator {
+            height: height.max(1),
+            width: width.max(1),
+            words: Vec::new(),
+            directions: Direction::ALL.to_vec(),
+            seed: 0,
+            max_overlap: 1,
+            alphabet: None,
+            attempts: 100,
+        }
+    }
+
+    /// Words to hide. A word listed twice is hidden twice. A word and its reverse read
+    /// the same cells, so list only one of them.
+    pub fn words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.words = words.into_iter().map(|w| w.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Directions words may be placed along.
+    pub fn directions(mut self, directions: &[Direction]) -> Self {
+        self.directions = directions.to_vec();
+        self
+    }
+
+    /// Seed for reproducible puzzles.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Most cells a word may share with the words placed before it, 0 for none.
+    pub fn max_overlap(mut self, cells: usize) -> Self {
+        self.max_overlap = cells;
+        self
+    }
+
+    /// Letters for the cells no word covers, all printable ASCII. Defaults to the
+    /// letters of the words, which makes the best distractors.
+    pub fn alphabet(mut self, letters: &str) -> Self {
+        self.alphabet = Some(letters.to_string());
+        self
+    }
+
+    /// Fresh starts to try before giving up.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Build the puzzle and its answer key.
+    pub fn generate(&self) -> Result<GeneratedPuzzle, GeneratePuzzleError> {
+        if self.directions.is_empty() {
+            return Err(GeneratePuzzleError::NoDirections);
+        }
+        if let Some(letters) = &self.alphabet {
+            if letters.is_empty() || !letters.bytes().all(|b| b.is_ascii_graphic()) {
+                return Err(GeneratePuzzleError::InvalidAlphabet(letters.clone()));
+            }
+        }
+        for word in &self.words {
+            if word.is_empty() || !word.bytes().all(|b| b.is_ascii_graphic()) {
+                return Err(GeneratePuzzleError::InvalidWord(word.clone()));
+            }
+            if !self
+                .directions
+                .iter()
+                .any(|&dir| self.fits(word.len(), dir))
+            {
+                return Err(GeneratePuzzleError::DoesNotFit(word.clone()));
+            }
+        }
+
+        let mut alphabet: Vec<u8> = match &self.alphabet {
+            Some(letters) => letters.bytes().collect(),
+            None => self.words.iter().flat_map(|w| w.bytes()).collect(),
+        };
+        if alphabet.is_empty() {
+            return Err(GeneratePuzzleError::InvalidAlphabet(String::new()));
+        }
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let mut distinct = self.words.clone();
+        distinct.sort();
+        distinct.dedup();
+
+        // Longest words first, while the grid still has room for them.
+        let mut order = self.words.clone();
+        order.sort_by_key(|word| std::cmp::Reverse(word.len()));
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        for _ in 0..self.attempts {
+            let mut attempt = Attempt {
+                generator: self,
+                cells: vec![EMPTY; self.height * self.width],
+                placed: Vec::new(),
+                distinct: &distinct,
+            };
+            if order.iter().all(|word| attempt.place(word, &mut rng))
+                && attempt.fill(&alphabet, &mut rng)
+            {
+                let grid = Grid::from_cells(attempt.cells, self.width);
+                let mut listed: Vec<&String> = Vec::new();
+                let mut answers = Vec::new();
+                for word in &self.words {
+                    if !listed.contains(&word) {
+                        listed.push(word);
+                        answers.extend(grid.find_word(word).into_iter().map(|found| Answer {
+                            word: word.clone(),
+                            found,
+                        }));
+                    }
+                }
+                return Ok(GeneratedPuzzle { grid, answers });
+            }
+        }
+
+        Err(GeneratePuzzleError::Exhausted {
+            attempts: self.attempts,
+        })
+    }
+
+    /// Whether a word of `len` letters fits the grid along `dir`.
+    fn fits(&self, len: usize, dir: Direction) -> bool {
+        (dir.dy == 0 || len <= self.height) && (dir.dx == 0 || len <= self.width)
+    }
+}
+
+/// One try at building a puzzle, abandoned as soon as it goes wrong.
+struct Attempt<'a> {
+    generator: &'a PuzzleGenerator,
+    cells: Vec<u8>,
+    placed: Vec<(String, WordMatch)>,
+    distinct: &'a [String],
+}
+
+impl Attempt<'_> {
+    /// Place `word` at a random position that keeps every word's matches exactly
+    /// those that were placed.
+    fn place(&mut self, word: &str, rng: &mut ChaCha8Rng) -> bool {
+        let (height, width) = (self.generator.height, self.generator.width);
+        let len = word.len();
+        // A single letter reads the same every way, and is reported reading to the right.
+        let directions = if len == 1 {
+            &[Direction::RIGHT][..]
+        } else {
+            &self.generator.directions[..]
+        };
+        let mut candidates = Vec::new();
+        for &dir in directions {
+            for row in 0..height {
+                for col in 0..width {
+                    let end_row = row as isize + dir.dy as isize * (len as isize - 1);
+                    let end_col = col as isize + dir.dx as isize * (len as isize - 1);
+                    if (0..height as isize).contains(&end_row)
+                        && (0..width as isize).contains(&end_col)
+                    {
+                        candidates.push(WordMatch {
+                            row,
+                            col,
+                            direction: dir,
+                            len,
+                        });
+                    }
+                }
+            }
+        }
+        candidates.shuffle(rng);
+
+        for candidate in candidates {
+            let mut shared = 0;
+            let fits = candidate.cells().zip(word.bytes()).all(|((r, c), letter)| {
+                match self.cells[r * width + c] {
+                    EMPTY => true,
+                    existing if existing == letter => {
+                        shared += 1;
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            if !fits || shared > self.generator.max_overlap {
+                continue;
+            }
+
+            let before = self.cells.clone();
+            for ((r, c), letter) in candidate.cells().zip(word.bytes()) {
+                self.cells[r * width + c] = letter;
+            }
+            self.placed.push((word.to_string(), candidate));
+            if self.only_placed_matches() {
+                return true;
+            }
+            self.placed.pop();
+            self.cells = before;
+        }
+        false
+    }
+
+    /// Whether every word is found exactly where it was placed, and nowhere else.
+    fn only_placed_matches(&self) -> bool {
+        self.distinct.iter().all(|word| {
+            let mut expected = Vec::new();
+            for (placed, at) in &self.placed {
+                if placed == word {
+                    expected.push(*at);
+                    // A palindrome also reads backwards from its last letter.
+                    if at.len > 1 && word.bytes().rev().eq(word.bytes()) {
+                        let (row, col) = at.cells().last().unwrap();
+                        let direction = Direction {
+                            dy: -at.direction.dy,
+                            dx: -at.direction.dx,
+                        };
+                        expected.push(WordMatch {
+                            row,
+                            col,
+                            direction,
+                            len: at.len,
+                        });
+                    }
+                }
+            }
+
+            let mut found = find_word_in(
+                &self.cells,
+                self.generator.width,
+                word.as_bytes(),
+                0..self.generator.height,
+            );
+            let key = |m: &WordMatch| (m.row, m.col, m.direction.dy, m.direction.dx);
+            expected.sort_by_key(key);
+            found.sort_by_key(key);
+            expected == found
+        })
+    }
+
+    /// Fill every empty cell with a distractor that completes no word.
+    fn fill(&mut self, alphabet: &[u8], rng: &mut ChaCha8Rng) -> bool {
+        let mut letters = alphabet.to_vec();
+        for pos in 0..self.cells.len() {
+            if self.cells[pos] != EMPTY {
+                continue;
+            }
+            letters.shuffle(rng);
+            let chosen = letters.iter().copied().find(|&letter| {
+                self.cells[pos] = letter;
+                !self.completes_word(pos)
+            });
+            match chosen {
+                Some(letter) => self.cells[pos] = letter,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Whether some word now reads through the cell at `pos`. No placed word covers
+    /// that cell, so any such match would be unintended.
+    fn completes_word(&self, pos: usize) -> bool {
+        let (height, width) = (
+            self.generator.height as isize,
+            self.generator.width as isize,
+        );
+        let (row, col) = (
+            (pos / width as usize) as isize,
+            (pos % width as usize) as isize,
+        );
+        let letter = self.cells[pos];
+
+        self.distinct.iter().any(|word| {
+            let word = word.as_bytes();
+            Direction::ALL.iter().any(|dir| {
+                let (dy, dx) = (dir.dy as isize, dir.dx as isize);
+                (0..word.len()).filter(|&k| word[k] == letter).any(|k| {
+                    (0..word.len()).all(|i| {
+                        let r = row + dy * (i as isize - k as isize);
+                        let c = col + dx * (i as isize - k as isize);
+                        (0..height).contains(&r)
+                            && (0..width).contains(&c)
+                            && self.cells[(r * width + c) as usize] == word[i]
+                    })
+                })
+            })
+        })
+    }
+}
+
+/// A word and one place it is hidden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub word: String,
+    pub found: WordMatch,
+}
+
+/// A generated puzzle with its answer key.
+#[derive(Debug)]
+pub struct GeneratedPuzzle {
+    pub grid: Grid,
+    /// Every match of every word, grouped by word in the order they were listed.
+    pub answers: Vec<Answer>,
+}
+
+impl GeneratedPuzzle {
+    /// How many times `word` is hidden in the puzzle.
+    pub fn count(&self, word: &str) -> usize {
+        self.answers.iter().filter(|a| a.word == word).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 6] = ["XMAS", "SLEIGH", "ELF", "TINSEL", "STAR", "SNOW"];
+
+    #[test]
+    fn test_answer_counts_are_exact() {
+        for seed in 0..20 {
+            let puzzle = PuzzleGenerator::new(10, 12)
+                .words(WORDS)
+                .seed(seed)
+                .generate()
+                .unwrap();
+            assert_eq!(puzzle.grid.size(), (10, 12));
+            for word in WORDS {
+                assert_eq!(puzzle.count(word), 1, "{word} with seed {seed}");
+                assert_eq!(puzzle.grid.count_word(word), 1, "{word} with seed {seed}");
+            }
+            assert_eq!(puzzle.answers.len(), WORDS.len());
+        }
+    }
+
+    #[test]
+    fn test_directions_and_overlap() {
+        let puzzle = PuzzleGenerator::new(8, 8)
+            .words(["XMAS", "XMAS", "SANTA", "GIFT"])
+            .directions(&[Direction::RIGHT, Direction::DOWN])
+            .max_overlap(0)
+            .seed(7)
+            .generate()
+            .unwrap();
+        assert_eq!(puzzle.count("XMAS"), 2);
+        assert!(puzzle
+            .answers
+            .iter()
+            .all(|a| [Direction::RIGHT, Direction::DOWN].contains(&a.found.direction)));
+
+        // Without overlap, no cell belongs to two answers.
+        let mut cells: Vec<_> = puzzle
+            .answers
+            .iter()
+            .flat_map(|a| a.found.cells())
+            .collect();
+        let total = cells.len();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), total);
+    }
+
+    #[test]
+    fn test_generation_is_reproducible() {
+        let generator = PuzzleGenerator::new(9, 9).words(WORDS).seed(2024);
+        let first = generator.generate().unwrap();
+        let second = generator.generate().unwrap();
+        let other = generator.clone().seed(2025).generate().unwrap();
+        assert_eq!(first.grid.to_string(), second.grid.to_string());
+        assert_eq!(first.answers, second.answers);
+        assert_ne!(first.grid.to_string(), other.grid.to_string());
+    }
+
+    #[test]
+    fn test_impossible_puzzles() {
+        let generator = PuzzleGenerator::new(4, 4);
+        assert_eq!(
+            generator
+                .clone()
+                .words(["SNOWFLAKE"])
+                .generate()
+                .unwrap_err(),
+            GeneratePuzzleError::DoesNotFit("SNOWFLAKE".to_string())
+        );
+        assert_eq!(
+            generator.clone().words(["X MAS"]).generate().unwrap_err(),
+            GeneratePuzzleError::InvalidWord("X MAS".to_string())
+        );
+        // Distractors must be letters a grid can hold, and something must be placeable.
+        for letters in ["", "AB\0", "ÅÄÖ", "A B"] {
+            assert_eq!(
+                generator
+                    .clone()
+                    .words(["XMAS"])
+                    .alphabet(letters)
+                    .generate()
+                    .unwrap_err(),
+                GeneratePuzzleError::InvalidAlphabet(letters.to_string())
+            );
+        }
+        assert_eq!(
+            generator.clone().generate().unwrap_err(),
+            GeneratePuzzleError::InvalidAlphabet(String::new())
+        );
+        assert_eq!(
+            generator
+                .clone()
+                .words(["XMAS"])
+                .directions(&[])
+                .generate()
+                .unwrap_err(),
+            GeneratePuzzleError::NoDirections
+        );
+
+        // "MAS" can never be hidden apart from the "MAS" inside "XMAS".
+        assert_eq!(
+            generator
+                .words(["XMAS", "MAS"])
+                .attempts(3)
+                .generate()
+                .unwrap_err(),
+            GeneratePuzzleError::Exhausted { attempts: 3 }
+        );
+    }
+}
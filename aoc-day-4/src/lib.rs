@@ -1,42 +1,308 @@
 use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 
+mod bands;
+mod dictionary;
+mod generator;
+mod rays;
+mod render;
+mod stencil;
+mod torus;
+mod unicode;
+
+pub use bands::{StreamCounts, StreamingSearch, BAND_OVERLAP, DEFAULT_BAND_ROWS};
+pub use dictionary::{Dictionary, DictionaryMatches};
+pub use generator::{Answer, GeneratePuzzleError, GeneratedPuzzle, PuzzleGenerator};
+pub use rays::Ray;
+pub use render::Highlighted;
+pub use stencil::{Orientation, Orientations, ParseStencilError, Stencil, StencilMatch, WILDCARD};
+pub use torus::Torus;
+pub use unicode::{Segmentation, UnicodeGrid};
+
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
 
-#[derive(Debug)]
-pub struct ParseGridError;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// The puzzle has no rows, or its first row is blank.
+    Empty,
+    /// A row is wider or narrower than the first row.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// A cell holds something the grid cannot: a stray carriage return or, in a
+    /// byte [`Grid`], a non-ASCII letter. A [`UnicodeGrid`] also refuses whitespace
+    /// and control characters. `col` counts cells, not bytes.
+    InvalidChar { row: usize, col: usize, found: char },
+}
 
 impl fmt::Display for ParseGridError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Failed to parse grid - the Elf seems confused, please check the format!"
-        )
+        match self {
+            ParseGridError::Empty => write!(f, "Grid is empty - the Elf has nothing to search!"),
+            ParseGridError::RaggedRow {
+                row,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Grid row {} has {} cells, expected {}",
+                row + 1,
+                actual,
+                expected
+            ),
+            ParseGridError::InvalidChar { row, col, found } => {
+                write!(
+                    f,
+                    "Grid row {}, column {} holds {:?}, which is not a puzzle letter",
+                    row + 1,
+                    col + 1,
+                    found
+                )?;
+                if *found == '\r' {
+                    write!(f, " (a carriage return without a line feed?)")?;
+                } else if !found.is_ascii() {
+                    write!(f, " (Non-ASCII letters? Try a UnicodeGrid)")?;
+                }
+                Ok(())
+            }
+        }
     }
 }
 
 impl std::error::Error for ParseGridError {}
 
-#[derive(Debug, Clone, Copy)]
-struct Direction {
+/// How forgiving [`Grid::parse_with`] is about the layout of the text around the letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Rows end with `\n` or `\r\n`, with at most one after the last row.
+    #[default]
+    Strict,
+    /// Also accept blank lines after the last row.
+    Lenient,
+}
+
+/// Split a grid's text into rows, dropping the line endings `mode` allows.
+fn grid_lines(s: &str, mode: ParseMode) -> impl Iterator<Item = &str> + Clone {
+    match mode {
+        ParseMode::Strict => s.lines(),
+        ParseMode::Lenient => s.trim_end_matches(['\r', '\n']).lines(),
+    }
+}
+
+/// Check one row against the grid's width, reporting the first bad cell.
+/// A byte cell holds any ASCII character but a carriage return, which would
+/// break the grid's rows when it is printed back.
+fn check_row(row: usize, line: &[u8], width: usize) -> Result<(), ParseGridError> {
+    if let Some(col) = line
+        .iter()
+        .position(|&byte| !byte.is_ascii() || byte == b'\r')
+    {
+        let found = String::from_utf8_lossy(&line[col..])
+            .chars()
+            .next()
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        return Err(ParseGridError::InvalidChar { row, col, found });
+    }
+    if line.len() != width {
+        return Err(ParseGridError::RaggedRow {
+            row,
+            expected: width,
+            actual: line.len(),
+        });
+    }
+    Ok(())
+}
+
+// Diagonal letter pairs that spell "MAS" forwards or backwards through an 'A'.
+const MS_PATTERN: u16 = ((b'M' as u16) << 8) | (b'S' as u16);
+const SM_PATTERN: u16 = ((b'S' as u16) << 8) | (b'M' as u16);
+
+/// Whether the four diagonal neighbours of an 'A' complete an "X-MAS" cross.
+#[inline(always)]
+fn is_x_mas(ul: u8, ur: u8, ll: u8, lr: u8) -> bool {
+    let ul_lr: u16 = ((ul as u16) << 8) | (lr as u16);
+    let ur_ll: u16 = ((ur as u16) << 8) | (ll as u16);
+    (ul_lr == MS_PATTERN || ul_lr == SM_PATTERN) && (ur_ll == MS_PATTERN || ur_ll == SM_PATTERN)
+}
+
+/// Instruction set used by the "X-MAS" cross search.
+/// The Elf picks the fastest magnifying glass that fits the machine in front of her.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossSearchPath {
+    /// 32 columns per step, x86_64 CPUs with AVX2 only.
+    Avx2,
+    /// 16 columns per step, every x86_64 CPU.
+    Sse2,
+    /// One column per step, every target.
+    Scalar,
+}
+
+impl CrossSearchPath {
+    /// Every search path, fastest first.
+    pub const ALL: [CrossSearchPath; 3] = [
+        CrossSearchPath::Avx2,
+        CrossSearchPath::Sse2,
+        CrossSearchPath::Scalar,
+    ];
+
+    /// The fastest path supported by the running CPU.
+    pub fn detect() -> CrossSearchPath {
+        CrossSearchPath::ALL
+            .into_iter()
+            .find(|path| path.is_supported())
+            .unwrap_or(CrossSearchPath::Scalar)
+    }
+
+    /// Whether the running CPU can take this path.
+    pub fn is_supported(self) -> bool {
+        match self {
+            #[cfg(target_arch = "x86_64")]
+            CrossSearchPath::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            CrossSearchPath::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(not(target_arch = "x86_64"))]
+            CrossSearchPath::Avx2 | CrossSearchPath::Sse2 => false,
+            CrossSearchPath::Scalar => true,
+        }
+    }
+}
+
+/// A step across the grid, one of the eight compass directions.
+/// The Elf has drawn small arrows in eight directions, making sure you know where to look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Direction {
     dy: i8,
     dx: i8,
 }
 
 impl Direction {
-    /// All possible directions to search for the word "XMAS".
-    /// The Elf has drawn small arrows in eight directions, making sure you know where to look.
-    const ALL: [Direction; 8] = [
-        Direction { dy: 0, dx: 1 },   // Right
-        Direction { dy: 1, dx: 0 },   // Down
-        Direction { dy: 0, dx: -1 },  // Left
-        Direction { dy: -1, dx: 0 },  // Up
-        Direction { dy: 1, dx: 1 },   // DownRight
-        Direction { dy: 1, dx: -1 },  // DownLeft
-        Direction { dy: -1, dx: 1 },  // UpRight
-        Direction { dy: -1, dx: -1 }, // UpLeft
+    pub const RIGHT: Direction = Direction { dy: 0, dx: 1 };
+    pub const DOWN: Direction = Direction { dy: 1, dx: 0 };
+    pub const LEFT: Direction = Direction { dy: 0, dx: -1 };
+    pub const UP: Direction = Direction { dy: -1, dx: 0 };
+    pub const DOWN_RIGHT: Direction = Direction { dy: 1, dx: 1 };
+    pub const DOWN_LEFT: Direction = Direction { dy: 1, dx: -1 };
+    pub const UP_RIGHT: Direction = Direction { dy: -1, dx: 1 };
+    pub const UP_LEFT: Direction = Direction { dy: -1, dx: -1 };
+
+    /// All possible directions to search for a word.
+    pub const ALL: [Direction; 8] = [
+        Direction::RIGHT,
+        Direction::DOWN,
+        Direction::LEFT,
+        Direction::UP,
+        Direction::DOWN_RIGHT,
+        Direction::DOWN_LEFT,
+        Direction::UP_RIGHT,
+        Direction::UP_LEFT,
     ];
+
+    /// Row step, -1, 0 or 1.
+    pub fn dy(&self) -> i8 {
+        self.dy
+    }
+
+    /// Column step, -1, 0 or 1.
+    pub fn dx(&self) -> i8 {
+        self.dx
+    }
+}
+
+/// A word found in the grid, starting at `(row, col)` and reading along `direction`.
+/// The Elf circles each discovery in red ink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+    pub len: usize,
+}
+
+impl WordMatch {
+    /// The `(row, col)` of every letter in the match, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len).map(move |step| {
+            (
+                (self.row as isize + self.direction.dy as isize * step as isize) as usize,
+                (self.col as isize + self.direction.dx as isize * step as isize) as usize,
+            )
+        })
+    }
+}
+
+/// Find the matches of `word` in rows of `width` cells laid end to end, keeping those
+/// whose topmost letter lies in `rows`. Shared by the byte and Unicode grids.
+fn find_word_in<T: PartialEq>(
+    cells: &[T],
+    width: usize,
+    word: &[T],
+    rows: Range<usize>,
+) -> Vec<WordMatch> {
+    let height = cells.len() / width;
+    let Some((first, rest)) = word.split_first() else {
+        return Vec::new();
+    };
+    let directions: &[Direction] = if rest.is_empty() {
+        &Direction::ALL[..1]
+    } else {
+        &Direction::ALL
+    };
+    let reach = rest.len() as isize;
+    let mut matches = Vec::new();
+
+    // Words reading upwards start below their topmost row.
+    let last_row = (rows.end + rest.len()).min(height);
+    let scan = rows.start * width..last_row * width;
+
+    // Starting the search from each cell holding the first letter.
+    for (pos, _) in cells[scan.clone()]
+        .iter()
+        .enumerate()
+        .filter(|&(_, c)| c == first)
+    {
+        let pos = scan.start + pos;
+        let (row, col) = (pos / width, pos % width);
+
+        for &dir in directions {
+            // Calculating the endpoint for the direction we're exploring.
+            let end_row = row as isize + dir.dy as isize * reach;
+            let end_col = col as isize + dir.dx as isize * reach;
+
+            // Ensure the endpoint is within the bounds of the grid.
+            if end_row < 0 || end_row >= height as isize || end_col < 0 || end_col >= width as isize
+            {
+                continue;
+            }
+
+            // Leave words whose topmost row is outside `rows` to another band.
+            if !rows.contains(&(row.min(end_row as usize))) {
+                continue;
+            }
+
+            // Step along the direction checking the remaining letters.
+            let step = dir.dy as isize * width as isize + dir.dx as isize;
+            let found = rest.iter().enumerate().all(|(i, letter)| {
+                let at = (pos as isize + step * (i as isize + 1)) as usize;
+                // SAFETY: both endpoints are in bounds, so every cell between them is too.
+                unsafe { cells.get_unchecked(at) == letter }
+            });
+
+            if found {
+                matches.push(WordMatch {
+                    row,
+                    col,
+                    direction: dir,
+                    len: word.len(),
+                });
+            }
+        }
+    }
+
+    matches
 }
 
 #[derive(Debug)]
@@ -44,7 +310,6 @@ pub struct Grid {
     cells: Vec<u8>,
     height: usize,
     width: usize,
-    x_positions: Vec<(usize, usize)>,
     #[cfg(target_arch = "x86_64")]
     row_offsets: Vec<usize>,
 }
@@ -52,43 +317,48 @@ pub struct Grid {
 impl FromStr for Grid {
     type Err = ParseGridError;
 
+    /// Parse a grid in [`ParseMode::Strict`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let width = s.lines().next().ok_or(ParseGridError)?.len();
-        let mut cells = Vec::with_capacity(s.len());
-        let mut x_positions = Vec::new();
-
-        // The Elf watches closely as each row is processed, making sure no "X" is missed.
-        for (row, line) in s.lines().enumerate() {
-            if line.len() != width {
-                return Err(ParseGridError);
-            }
+        Grid::parse_with(s, ParseMode::Strict)
+    }
+}
 
-            for (col, &byte) in line.as_bytes().iter().enumerate() {
-                if byte == b'X' {
-                    x_positions.push((row, col));
-                }
-            }
+impl Grid {
+    /// Parse a grid, one row per line, every row as wide as the first.
+    /// Errors point at the row and column the Elf tripped over.
+    pub fn parse_with(s: &str, mode: ParseMode) -> Result<Grid, ParseGridError> {
+        let lines = grid_lines(s, mode);
+        let width = lines.clone().next().map_or(0, str::len);
+        if width == 0 {
+            return Err(ParseGridError::Empty);
+        }
+        let mut cells = Vec::with_capacity(s.len());
 
+        // The Elf watches closely as each row is processed, making sure none is out of line.
+        for (row, line) in lines.enumerate() {
+            check_row(row, line.as_bytes(), width)?;
             cells.extend_from_slice(line.as_bytes());
         }
 
+        Ok(Grid::from_cells(cells, width))
+    }
+
+    /// Build a grid from its rows laid end to end, each `width` letters long.
+    fn from_cells(cells: Vec<u8>, width: usize) -> Grid {
         let height = cells.len() / width;
 
         #[cfg(target_arch = "x86_64")]
         let row_offsets: Vec<usize> = (0..height).map(|r| r * width).collect();
 
-        Ok(Grid {
+        Grid {
             cells,
             height,
             width,
-            x_positions,
             #[cfg(target_arch = "x86_64")]
             row_offsets,
-        })
+        }
     }
-}
 
-impl Grid {
     /// Retrieve the value at a specific position in the grid.
     /// The Elf peeks over your shoulder as you double-check each character.
     #[inline(always)]
@@ -97,66 +367,60 @@ impl Grid {
         unsafe { *self.cells.get_unchecked(row * self.width + col) }
     }
 
+    /// Find every occurrence of `word`, reading in any of the eight directions.
+    /// A single letter matches once per cell, reported as reading to the right.
+    /// The Elf hands you a fresh word from her list and waits to see where it hides.
+    pub fn find_word(&self, word: &str) -> Vec<WordMatch> {
+        self.find_word_in_rows(word, 0..self.height)
+    }
+
+    /// Find the matches of `word` whose topmost letter lies in `rows`.
+    /// Only `rows` and the `word.len() - 1` rows after them are read.
+    fn find_word_in_rows(&self, word: &str, rows: Range<usize>) -> Vec<WordMatch> {
+        find_word_in(&self.cells, self.width, word.as_bytes(), rows)
+    }
+
+    /// Count the occurrences of `word` in any of the eight directions.
+    pub fn count_word(&self, word: &str) -> usize {
+        self.find_word(word).len()
+    }
+
     /// Find all occurrences of the word "XMAS" within the grid.
     /// The Elf's excitement grows as you uncover each "XMAS" hidden in the puzzle.
     pub fn find_word_xmas(&self) -> usize {
-        let mut count = 0;
-
-        // Starting the search for each 'X' found in the grid.
-        for &(row, col) in &self.x_positions {
-            let pos = row * self.width + col;
-
-            for dir in Direction::ALL {
-                // Calculating the endpoint for the direction we're exploring.
-                let end_row = row as i32 + dir.dy as i32 * 3;
-                let end_col = col as i32 + dir.dx as i32 * 3;
-
-                // Ensure the endpoint is within the bounds of the grid.
-                if end_row < 0
-                    || end_row >= self.height as i32
-                    || end_col < 0
-                    || end_col >= self.width as i32
-                {
-                    continue;
-                }
-
-                // Calculate the position offsets for checking "MAS" following the 'X'.
-                let base_offset = dir.dy as isize * self.width as isize + dir.dx as isize;
-
-                unsafe {
-                    let pos1 = (pos as isize + base_offset) as usize;
-                    let pos2 = (pos1 as isize + base_offset) as usize;
-                    let pos3 = (pos2 as isize + base_offset) as usize;
-
-                    // The Elf cheers as you find another occurrence of "XMAS"!
-                    if *self.cells.get_unchecked(pos1) == b'M'
-                        && *self.cells.get_unchecked(pos2) == b'A'
-                        && *self.cells.get_unchecked(pos3) == b'S'
-                    {
-                        count += 1;
-                    }
-                }
-            }
-        }
+        self.count_word("XMAS")
+    }
 
-        count
+    /// Check the four diagonal neighbours of the 'A' at column `c` of the row
+    /// between `above_offset` and `below_offset`.
+    ///
+    /// # Safety
+    /// `c - 1` and `c + 1` must be columns of the grid, and both offsets must start rows.
+    #[cfg(target_arch = "x86_64")]
+    #[inline(always)]
+    unsafe fn x_mas_at(&self, above_offset: usize, below_offset: usize, c: usize) -> bool {
+        is_x_mas(
+            *self.cells.get_unchecked(above_offset + c - 1),
+            *self.cells.get_unchecked(above_offset + c + 1),
+            *self.cells.get_unchecked(below_offset + c - 1),
+            *self.cells.get_unchecked(below_offset + c + 1),
+        )
     }
 
-    /// SIMD-optimized search for the X-MAS cross pattern for x86_64 architectures.
+    /// AVX2 search for the X-MAS cross pattern, 32 columns at a time.
     /// The Elf is fascinated by the speed with which this part of the search happens.
+    ///
+    /// # Safety
+    /// The CPU must support AVX2, and `rows` must have a row above and below each of them.
     #[cfg(target_arch = "x86_64")]
     #[inline(never)]
     #[target_feature(enable = "avx2")]
-    unsafe fn find_crossed_mas_simd(&self) -> usize {
+    unsafe fn find_crossed_mas_avx2(&self, rows: Range<usize>) -> usize {
         let mut count = 0;
         let a_pattern = _mm256_set1_epi8(b'A' as i8);
 
-        // Constants for pattern matching
-        const MS_PATTERN: u16 = ((b'M' as u16) << 8) | (b'S' as u16);
-        const SM_PATTERN: u16 = ((b'S' as u16) << 8) | (b'M' as u16);
-
         // Process 32 positions at once
-        for row in 1..self.height - 1 {
+        for row in rows {
             let row_offset = *self.row_offsets.get_unchecked(row);
             let above_offset = *self.row_offsets.get_unchecked(row - 1);
             let below_offset = *self.row_offsets.get_unchecked(row + 1);
@@ -167,85 +431,134 @@ impl Grid {
                     self.cells.get_unchecked(row_offset + col) as *const u8 as *const __m256i
                 );
                 let a_matches = _mm256_cmpeq_epi8(center, a_pattern);
-                let mask = _mm256_movemask_epi8(a_matches) as u32;
-
-                if mask != 0 {
-                    let mut bit = mask;
-                    while bit != 0 {
-                        let pos = bit.trailing_zeros() as usize;
-                        let c = col + pos;
-
-                        // Load diagonal characters to check for 'M' and 'S'
-                        let ul = *self.cells.get_unchecked(above_offset + c - 1);
-                        let ur = *self.cells.get_unchecked(above_offset + c + 1);
-                        let ll = *self.cells.get_unchecked(below_offset + c - 1);
-                        let lr = *self.cells.get_unchecked(below_offset + c + 1);
-
-                        let ul_lr: u16 = ((ul as u16) << 8) | (lr as u16);
-                        let ur_ll: u16 = ((ur as u16) << 8) | (ll as u16);
-
-                        // If we find a valid 'MAS' cross pattern, the Elf claps with joy.
-                        count += ((ul_lr == MS_PATTERN || ul_lr == SM_PATTERN)
-                            && (ur_ll == MS_PATTERN || ur_ll == SM_PATTERN))
-                            as usize;
-
-                        bit &= bit - 1; // Clear least significant set bit
-                    }
+                let mut bit = _mm256_movemask_epi8(a_matches) as u32;
+
+                while bit != 0 {
+                    let c = col + bit.trailing_zeros() as usize;
+                    // If we find a valid 'MAS' cross pattern, the Elf claps with joy.
+                    count += self.x_mas_at(above_offset, below_offset, c) as usize;
+                    bit &= bit - 1; // Clear least significant set bit
                 }
                 col += 32;
             }
 
-            // Handle remaining positions
-            while col < self.width - 1 {
-                if *self.cells.get_unchecked(row_offset + col) == b'A' {
-                    let ul = *self.cells.get_unchecked(above_offset + col - 1);
-                    let ur = *self.cells.get_unchecked(above_offset + col + 1);
-                    let ll = *self.cells.get_unchecked(below_offset + col - 1);
-                    let lr = *self.cells.get_unchecked(below_offset + col + 1);
+            count += self.crossed_mas_row_tail(row, col);
+        }
+        count
+    }
 
-                    let ul_lr: u16 = ((ul as u16) << 8) | (lr as u16);
-                    let ur_ll: u16 = ((ur as u16) << 8) | (ll as u16);
+    /// SSE2 search for the X-MAS cross pattern, 16 columns at a time.
+    ///
+    /// # Safety
+    /// The CPU must support SSE2, which every x86_64 CPU does, and `rows` must have a
+    /// row above and below each of them.
+    #[cfg(target_arch = "x86_64")]
+    #[inline(never)]
+    #[target_feature(enable = "sse2")]
+    unsafe fn find_crossed_mas_sse2(&self, rows: Range<usize>) -> usize {
+        let mut count = 0;
+        let a_pattern = _mm_set1_epi8(b'A' as i8);
 
-                    count += ((ul_lr == MS_PATTERN || ul_lr == SM_PATTERN)
-                        && (ur_ll == MS_PATTERN || ur_ll == SM_PATTERN))
-                        as usize;
+        for row in rows {
+            let row_offset = *self.row_offsets.get_unchecked(row);
+            let above_offset = *self.row_offsets.get_unchecked(row - 1);
+            let below_offset = *self.row_offsets.get_unchecked(row + 1);
+
+            let mut col = 1;
+            while col + 16 < self.width - 1 {
+                let center = _mm_loadu_si128(
+                    self.cells.get_unchecked(row_offset + col) as *const u8 as *const __m128i,
+                );
+                let a_matches = _mm_cmpeq_epi8(center, a_pattern);
+                let mut bit = _mm_movemask_epi8(a_matches) as u32;
+
+                while bit != 0 {
+                    let c = col + bit.trailing_zeros() as usize;
+                    count += self.x_mas_at(above_offset, below_offset, c) as usize;
+                    bit &= bit - 1;
                 }
-                col += 1;
+                col += 16;
             }
+
+            count += self.crossed_mas_row_tail(row, col);
         }
         count
     }
 
-    /// Find all occurrences of the "X-MAS" cross pattern within the grid.
-    /// The Elf looks amazed every time you uncover another 'X-MAS' cross formation.
-    pub fn find_crossed_mas(&self) -> usize {
-        #[cfg(target_arch = "x86_64")]
-        unsafe {
-            self.find_crossed_mas_simd()
+    /// Handle the columns of `row` from `col` on that did not fill a whole SIMD lane.
+    ///
+    /// # Safety
+    /// `row` must have a row above and below it, and `col` must be at least 1.
+    #[cfg(target_arch = "x86_64")]
+    #[inline(always)]
+    unsafe fn crossed_mas_row_tail(&self, row: usize, mut col: usize) -> usize {
+        let row_offset = *self.row_offsets.get_unchecked(row);
+        let above_offset = *self.row_offsets.get_unchecked(row - 1);
+        let below_offset = *self.row_offsets.get_unchecked(row + 1);
+        let mut count = 0;
+
+        while col < self.width - 1 {
+            if *self.cells.get_unchecked(row_offset + col) == b'A' {
+                count += self.x_mas_at(above_offset, below_offset, col) as usize;
+            }
+            col += 1;
         }
+        count
+    }
 
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            let mut count = 0;
-            for row in 1..self.height - 1 {
-                let row_offset = row * self.width;
-                for col in 1..self.width - 1 {
-                    if self.cells[row_offset + col] == b'A' {
-                        let ul = self.cells[row_offset - self.width + col - 1];
-                        let ur = self.cells[row_offset - self.width + col + 1];
-                        let ll = self.cells[row_offset + self.width + col - 1];
-                        let lr = self.cells[row_offset + self.width + col + 1];
-
-                        let ul_lr: u16 = ((ul as u16) << 8) | (lr as u16);
-                        let ur_ll: u16 = ((ur as u16) << 8) | (ll as u16);
-
-                        count += ((ul_lr == MS_PATTERN || ul_lr == SM_PATTERN)
-                            && (ur_ll == MS_PATTERN || ur_ll == SM_PATTERN))
-                            as usize;
-                    }
+    /// Portable search for the X-MAS cross pattern centred on `rows`, one cell at a time.
+    /// The Elf double-checks every 'A' by hand, on any machine she is given.
+    fn find_crossed_mas_scalar(&self, rows: Range<usize>) -> usize {
+        let mut count = 0;
+        for row in rows {
+            for col in 1..self.width.saturating_sub(1) {
+                if self.get(row, col) == b'A' {
+                    count += is_x_mas(
+                        self.get(row - 1, col - 1),
+                        self.get(row - 1, col + 1),
+                        self.get(row + 1, col - 1),
+                        self.get(row + 1, col + 1),
+                    ) as usize;
                 }
             }
-            count
+        }
+        count
+    }
+
+    /// Find all occurrences of the "X-MAS" cross pattern within the grid, using the
+    /// fastest search the running CPU supports.
+    /// The Elf looks amazed every time you uncover another 'X-MAS' cross formation.
+    pub fn find_crossed_mas(&self) -> usize {
+        self.find_crossed_mas_with(CrossSearchPath::detect())
+            .expect("detected search path is supported")
+    }
+
+    /// Find all "X-MAS" crosses using a specific search path, or `None` if the
+    /// running CPU does not support it.
+    pub fn find_crossed_mas_with(&self, path: CrossSearchPath) -> Option<usize> {
+        if !path.is_supported() {
+            return None;
+        }
+        Some(self.crossed_mas_in_rows(path, 0..self.height))
+    }
+
+    /// Count the "X-MAS" crosses whose top row lies in `rows`, with a supported path.
+    /// Only `rows` and the two rows after them are read.
+    fn crossed_mas_in_rows(&self, path: CrossSearchPath, rows: Range<usize>) -> usize {
+        // A cross is centred one row below its top row, and never on the grid's edge.
+        let centres = (rows.start + 1)..(rows.end + 1).min(self.height.saturating_sub(1));
+        if centres.is_empty() {
+            return 0;
+        }
+
+        match path {
+            // SAFETY: callers checked is_supported, and every centre row has a row
+            // above and below it.
+            #[cfg(target_arch = "x86_64")]
+            CrossSearchPath::Avx2 => unsafe { self.find_crossed_mas_avx2(centres) },
+            #[cfg(target_arch = "x86_64")]
+            CrossSearchPath::Sse2 => unsafe { self.find_crossed_mas_sse2(centres) },
+            _ => self.find_crossed_mas_scalar(centres),
         }
     }
 
@@ -269,3 +582,62 @@ impl fmt::Display for Grid {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a pseudo-random grid over the puzzle's letters.
+    pub(crate) fn letter_soup(height: usize, width: usize, seed: u64) -> Grid {
+        let mut state = seed;
+        let rows: Vec<String> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ['X', 'M', 'A', 'S'][(state >> 62) as usize]
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.join("\n").parse().unwrap()
+    }
+
+    #[test]
+    fn test_cross_search_paths_agree() {
+        assert!(CrossSearchPath::Scalar.is_supported());
+        assert!(CrossSearchPath::detect().is_supported());
+
+        // Widths straddle the 16 and 32 column lanes and their tails.
+        for (seed, (height, width)) in [
+            (1, 1),
+            (3, 3),
+            (5, 17),
+            (7, 33),
+            (40, 34),
+            (9, 70),
+            (64, 64),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let grid = letter_soup(height, width, seed as u64);
+            let expected = grid.find_crossed_mas_with(CrossSearchPath::Scalar);
+            for path in CrossSearchPath::ALL {
+                if path.is_supported() {
+                    assert_eq!(
+                        grid.find_crossed_mas_with(path),
+                        expected,
+                        "{path:?} on a {height}x{width} grid"
+                    );
+                }
+            }
+            assert_eq!(
+                expected,
+                Some(grid.count_stencil(&Stencil::x_mas(), Orientations::Rotations))
+            );
+        }
+    }
+}
@@ -5,22 +5,109 @@
 //! After helping the Elf find "XMAS" hidden across various directions, the real challenge emerges. The "X-MAS" puzzle requires finding a more intricate cross pattern involving two "MAS" words.
 //! This file serves as the entry point to launch the Elf's adventure and solve the word search.
 
-use aoc_day_4::Grid;
+use aoc_day_4::{
+    Grid, Orientations, ParseMode, Segmentation, Stencil, StreamingSearch, UnicodeGrid,
+};
 use std::env;
 use std::fs;
+use std::io::BufReader;
+
+const USAGE: &str =
+    "<input_file> [--lenient] [--stream] [--torus] [--unicode] [--show words|crosses] [--ansi] [--html <output_file>] [--dim]";
+
+/// Explain a malformed command line and stop.
+fn usage_error(program: &str, problem: &str, found: Option<&str>) -> ! {
+    match found {
+        Some(found) => eprintln!("⚠️ Oh dear! {} (got '{}')", problem, found),
+        None => eprintln!("⚠️ Oh dear! {}", problem),
+    }
+    eprintln!("Usage: {} {}", program, USAGE);
+    std::process::exit(1);
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("⚠️ Oh dear! It seems you've forgotten to provide the input file. Usage: {} <input_file>", args[0]);
+    let mut input_file = None;
+    let mut show_crosses = false;
+    let mut ansi = false;
+    let mut html_file = None;
+    let mut dim = false;
+    let mut stream = false;
+    let mut torus = false;
+    let mut unicode = false;
+    let mut mode = ParseMode::Strict;
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--show" => {
+                show_crosses = match rest.next().map(String::as_str) {
+                    Some("words") => false,
+                    Some("crosses") => true,
+                    other => usage_error(&args[0], "--show needs 'words' or 'crosses'", other),
+                }
+            }
+            "--ansi" => ansi = true,
+            "--html" => match rest.next() {
+                Some(path) if !path.starts_with("--") => html_file = Some(path),
+                other => usage_error(
+                    &args[0],
+                    "--html needs an output file",
+                    other.map(String::as_str),
+                ),
+            },
+            "--dim" => dim = true,
+            "--stream" => stream = true,
+            "--torus" => torus = true,
+            "--unicode" => unicode = true,
+            "--lenient" => mode = ParseMode::Lenient,
+            flag if flag.starts_with("--") => usage_error(&args[0], "unknown option", Some(flag)),
+            _ => input_file = Some(arg),
+        }
+    }
+
+    let Some(input_file) = input_file else {
+        eprintln!(
+            "⚠️ Oh dear! It seems you've forgotten to provide the input file. Usage: {} {}",
+            args[0], USAGE
+        );
         std::process::exit(1);
+    };
+
+    if stream {
+        // The Elf feeds a puzzle too big for her desk through her reading frame.
+        let counts = StreamingSearch::new().search(BufReader::new(fs::File::open(input_file)?))?;
+        println!(
+            "\n📜 Streamed {} rows\n🔍 Total 'XMAS' Instances Found: {}\n📌 Total 'X-MAS' Cross Patterns Found: {}\n",
+            counts.rows, counts.words, counts.crosses
+        );
+        return Ok(());
+    }
+
+    let input = fs::read_to_string(input_file)?;
+
+    if unicode {
+        // The Elf squints at letters from a puzzle written in another alphabet.
+        let grid = UnicodeGrid::parse_with(&input, Segmentation::Graphemes, mode)?;
+        println!(
+            "\n🌍 Unicode grid of {:?} cells\n🔍 Total 'XMAS' Instances Found: {}\n📌 Total 'X-MAS' Cross Patterns Found: {}\n",
+            grid.size(),
+            grid.count_word("XMAS"),
+            grid.find_crossed_mas()
+        );
+        return Ok(());
     }
 
-    let input = fs::read_to_string(&args[1])?;
-    let grid: Grid = input.parse()?;
-    let word_count = grid.find_word_xmas();
-    let cross_count = grid.find_crossed_mas();
+    let grid = Grid::parse_with(&input, mode)?;
+    // On a torus, words and crosses may wrap around the edges of the puzzle.
+    let (word_count, cross_count) = if torus {
+        (
+            grid.torus().count_word("XMAS"),
+            grid.torus().find_crossed_mas(),
+        )
+    } else {
+        (grid.find_word_xmas(), grid.find_crossed_mas())
+    };
     // The Elf watches intently as you search for the hidden XMAS words in her puzzle.
     println!(
         "\n✨ Magical Word Analysis: '\x1b[1;34mXMAS\x1b[0m' Found!\n🔍 Total 'XMAS' Instances Found: {}\n{}\n🧩 Cross Pattern Analysis: Searching for 'X-MAS' Crosses...\n📌 Total 'X-MAS' Cross Patterns Found: {}\n",
@@ -29,12 +116,49 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         cross_count
     );
 
+    if ansi || html_file.is_some() {
+        // The Elf grabs her highlighter to show exactly where each find is hiding.
+        let (title, cells): (&str, Vec<(usize, usize)>) = if show_crosses {
+            let crosses = if torus {
+                grid.torus()
+                    .find_stencil(&Stencil::x_mas(), Orientations::Rotations)
+            } else {
+                grid.find_stencil(&Stencil::x_mas(), Orientations::Rotations)
+            };
+            (
+                "X-MAS crosses",
+                crosses.into_iter().flat_map(|m| m.cells).collect(),
+            )
+        } else {
+            let cells = if torus {
+                let words = grid.torus().find_word("XMAS");
+                words.iter().flat_map(|m| grid.torus().cells(m)).collect()
+            } else {
+                let words = grid.find_word("XMAS");
+                words.iter().flat_map(|m| m.cells()).collect()
+            };
+            ("XMAS words", cells)
+        };
+        let view = grid.highlighted(cells).dim_unmatched(dim);
+
+        if ansi {
+            println!("{}", view.to_ansi());
+        }
+        if let Some(html_file) = html_file {
+            fs::write(html_file, view.to_html(title))?;
+            println!("🖍️ Highlighted {} written to {}", title, html_file);
+        }
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_day_4::{
+        Direction, Orientations, ParseGridError, ParseMode, PuzzleGenerator, Stencil, WordMatch,
+    };
 
     #[test]
     fn test_example_from_narrative() {
@@ -94,4 +218,160 @@ mod tests {
         let count = grid.find_crossed_mas();
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn test_find_word_records() {
+        let input = "..X...\n\
+                    .SAMX.\n\
+                    .A..A.\n\
+                    XMAS.S\n\
+                    .X....";
+        let grid: Grid = input.parse().unwrap();
+        let matches = grid.find_word("XMAS");
+        assert_eq!(matches.len(), 4);
+        assert!(matches.contains(&WordMatch {
+            row: 3,
+            col: 0,
+            direction: Direction::RIGHT,
+            len: 4,
+        }));
+        assert!(matches.contains(&WordMatch {
+            row: 1,
+            col: 4,
+            direction: Direction::LEFT,
+            len: 4,
+        }));
+
+        let found = matches
+            .iter()
+            .find(|m| m.direction == Direction::DOWN_RIGHT)
+            .unwrap();
+        assert_eq!(
+            found.cells().collect::<Vec<_>>(),
+            vec![(0, 2), (1, 3), (2, 4), (3, 5)]
+        );
+    }
+
+    #[test]
+    fn test_find_arbitrary_words() {
+        let input = "MMMSXXMASM\n\
+                    MSAMXMSMSA\n\
+                    AMXSXMAAMM\n\
+                    MSAMASMSMX\n\
+                    XMASAMXAMM\n\
+                    XXAMMXXAMA\n\
+                    SMSMSASXSS\n\
+                    SAXAMASAAA\n\
+                    MAMMMXMMMM\n\
+                    MXMXAXMASX";
+        let grid: Grid = input.parse().unwrap();
+        assert_eq!(grid.count_word("XMAS"), 18);
+        assert_eq!(grid.count_word("SAMX"), 18);
+        assert_eq!(grid.count_word("MMMSXXMASM"), 1);
+        assert_eq!(grid.count_word("MMMSXXMASMX"), 0);
+        assert_eq!(grid.count_word("S"), input.matches('S').count());
+        assert_eq!(grid.count_word(""), 0);
+    }
+
+    #[test]
+    fn test_x_mas_stencil_matches_crossed_mas() {
+        let input = ".M.S......\n\
+                    ..A..MSMS.\n\
+                    .M.S.MAA..\n\
+                    ..A.ASMSM.\n\
+                    .M.S.M....\n\
+                    ..........\n\
+                    S.S.S.S.S.\n\
+                    .A.A.A.A..\n\
+                    M.M.M.M.M.\n\
+                    ..........";
+        let grid: Grid = input.parse().unwrap();
+        assert_eq!(
+            grid.count_stencil(&Stencil::x_mas(), Orientations::Rotations),
+            9
+        );
+
+        let puzzle: Grid = fs::read_to_string("part_one.input")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            puzzle.count_stencil(&Stencil::x_mas(), Orientations::RotationsAndReflections),
+            puzzle.find_crossed_mas()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Grid>().unwrap_err(), ParseGridError::Empty);
+        assert_eq!(
+            "XMAS\nXMA\nXMAS".parse::<Grid>().unwrap_err(),
+            ParseGridError::RaggedRow {
+                row: 1,
+                expected: 4,
+                actual: 3,
+            }
+        );
+        // Spaces are letters like any other to a byte grid.
+        assert_eq!("XMAS\nXM S".parse::<Grid>().unwrap().row(1), b"XM S");
+        assert_eq!(
+            "XMAS\nXM\rS".parse::<Grid>().unwrap_err(),
+            ParseGridError::InvalidChar {
+                row: 1,
+                col: 2,
+                found: '\r',
+            }
+        );
+        assert_eq!(
+            "XMAS\nXMÅS".parse::<Grid>().unwrap_err(),
+            ParseGridError::InvalidChar {
+                row: 1,
+                col: 2,
+                found: 'Å',
+            }
+        );
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        // Windows line endings parse strictly, just as they always have.
+        let unix = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\n";
+        let windows = unix.replace('\n', "\r\n");
+        let grid: Grid = windows.parse().unwrap();
+        assert_eq!(grid.size(), (3, 10));
+        assert_eq!(grid.to_string(), unix.parse::<Grid>().unwrap().to_string());
+
+        // A blank line after the last row needs the lenient parser.
+        let trailing = windows + "\r\n";
+        assert_eq!(
+            trailing.parse::<Grid>().unwrap_err(),
+            ParseGridError::RaggedRow {
+                row: 3,
+                expected: 10,
+                actual: 0,
+            }
+        );
+        let grid = Grid::parse_with(&trailing, ParseMode::Lenient).unwrap();
+        assert_eq!(grid.size(), (3, 10));
+        let trailing = Grid::parse_with("XMAS\nSAMX\n\n\n", ParseMode::Lenient).unwrap();
+        assert_eq!(trailing.size(), (2, 4));
+        assert!("XMAS\nSAMX\n\n".parse::<Grid>().is_err());
+        assert!(Grid::parse_with("XMAS\n\nSAMX", ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_generated_puzzle() {
+        // A generated puzzle stands in for a hand-crafted one, answer key included.
+        let puzzle = PuzzleGenerator::new(10, 10)
+            .words(["XMAS", "XMAS", "XMAS", "SANTA"])
+            .seed(4)
+            .generate()
+            .unwrap();
+        println!("Generated grid:\n{}", puzzle.grid);
+        assert_eq!(puzzle.grid.find_word_xmas(), 3);
+        assert_eq!(puzzle.count("XMAS"), 3);
+        assert_eq!(puzzle.count("SANTA"), 1);
+        // Every "XMAS" reads as "SAMX" from its other end.
+        assert_eq!(puzzle.grid.count_word("SAMX"), 3);
+    }
 }
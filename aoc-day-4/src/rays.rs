@@ -0,0 +1,226 @@
+//! Reading a [`Grid`] line by line without copying it.
+//!
+//! Rows are plain byte slices. Columns, diagonals and rays from any cell are lazy
+//! [`Ray`] iterators that step through the grid's cells in place.
+
+use crate::{Direction, Grid};
+use std::iter::FusedIterator;
+
+/// The letters met walking from a cell in one direction until the edge of the grid.
+/// The Elf runs her finger along the puzzle, reading as she goes.
+#[derive(Debug, Clone)]
+pub struct Ray<'g> {
+    grid: &'g Grid,
+    start: (usize, usize),
+    direction: Direction,
+    front: usize,
+    back: usize,
+}
+
+impl Ray<'_> {
+    /// The cell the ray starts from.
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    /// The direction the ray reads in.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The `(row, col)` of the ray's `step`-th cell.
+    fn cell(&self, step: usize) -> (usize, usize) {
+        (
+            (self.start.0 as isize + self.direction.dy as isize * step as isize) as usize,
+            (self.start.1 as isize + self.direction.dx as isize * step as isize) as usize,
+        )
+    }
+
+    /// The `(row, col)` of every remaining cell, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.front..self.back).map(|step| self.cell(step))
+    }
+}
+
+impl Iterator for Ray<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        let (row, col) = self.cell(self.front);
+        self.front += 1;
+        Some(self.grid.get(row, col))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Ray<'_> {
+    fn next_back(&mut self) -> Option<u8> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let (row, col) = self.cell(self.back);
+        Some(self.grid.get(row, col))
+    }
+}
+
+impl ExactSizeIterator for Ray<'_> {}
+
+impl FusedIterator for Ray<'_> {}
+
+impl Grid {
+    /// The letters of one row.
+    pub fn row(&self, row: usize) -> &[u8] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// Every row, top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[u8]> + '_ {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// The letters of one column, top to bottom.
+    pub fn column(&self, col: usize) -> Ray<'_> {
+        self.ray(0, col, Direction::DOWN)
+    }
+
+    /// Every column, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = Ray<'_>> + '_ {
+        self.rays(Direction::DOWN)
+    }
+
+    /// Every diagonal reading down and to the right, from the bottom-left corner
+    /// round to the top-right corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = Ray<'_>> + '_ {
+        let starts = (0..self.height).rev().map(|row| (row, 0));
+        let starts = starts.chain((1..self.width).map(|col| (0, col)));
+        starts.map(|(row, col)| self.ray(row, col, Direction::DOWN_RIGHT))
+    }
+
+    /// Every diagonal reading down and to the left, from the top-left corner round
+    /// to the bottom-right corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Ray<'_>> + '_ {
+        let last_col = self.width - 1;
+        let starts = (0..self.width).map(|col| (0, col));
+        let starts = starts.chain((1..self.height).map(move |row| (row, last_col)));
+        starts.map(|(row, col)| self.ray(row, col, Direction::DOWN_LEFT))
+    }
+
+    /// The ray from `(row, col)` in direction `dir`, up to the edge of the grid.
+    ///
+    /// # Panics
+    /// If `(row, col)` is outside the grid.
+    pub fn ray(&self, row: usize, col: usize, dir: Direction) -> Ray<'_> {
+        assert!(
+            row < self.height && col < self.width,
+            "cell ({row}, {col}) is outside a {}x{} grid",
+            self.height,
+            self.width
+        );
+        let steps = |at: usize, step: i8, size: usize| match step {
+            1 => size - at,
+            -1 => at + 1,
+            _ => usize::MAX,
+        };
+        let len = steps(row, dir.dy, self.height).min(steps(col, dir.dx, self.width));
+
+        Ray {
+            grid: self,
+            start: (row, col),
+            direction: dir,
+            front: 0,
+            back: len,
+        }
+    }
+
+    /// Every ray in direction `dir` that starts on the edge of the grid, so that
+    /// together they cover each cell exactly once.
+    pub fn rays(&self, dir: Direction) -> impl Iterator<Item = Ray<'_>> + '_ {
+        (0..self.height)
+            .flat_map(move |row| (0..self.width).map(move |col| (row, col)))
+            .filter(move |&(row, col)| {
+                !self.contains(
+                    row as isize - dir.dy as isize,
+                    col as isize - dir.dx as isize,
+                )
+            })
+            .map(move |(row, col)| self.ray(row, col, dir))
+    }
+
+    fn contains(&self, row: isize, col: isize) -> bool {
+        row >= 0 && row < self.height as isize && col >= 0 && col < self.width as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::letter_soup;
+
+    #[test]
+    fn test_lines() {
+        let grid: Grid = "XMAS\nMASX\nASXM".parse().unwrap();
+        assert_eq!(grid.row(1), b"MASX");
+        assert_eq!(grid.rows().len(), 3);
+        assert_eq!(grid.column(3).collect::<Vec<_>>(), b"SXM");
+        assert_eq!(grid.columns().count(), 4);
+
+        let diagonals: Vec<Vec<u8>> = grid.diagonals().map(Ray::collect).collect();
+        assert_eq!(diagonals.len(), 6);
+        assert_eq!(diagonals[0], b"A");
+        assert_eq!(diagonals[2], b"XAX");
+        assert_eq!(diagonals[5], b"S");
+
+        let anti: Vec<Vec<u8>> = grid.anti_diagonals().map(Ray::collect).collect();
+        assert_eq!(anti.len(), 6);
+        assert_eq!(anti[2], b"AAA");
+        assert_eq!(anti[3], b"SSS");
+    }
+
+    #[test]
+    fn test_ray_from_a_cell() {
+        let grid: Grid = "XMAS\nMASX\nASXM".parse().unwrap();
+        let ray = grid.ray(2, 3, Direction::UP_LEFT);
+        assert_eq!(ray.len(), 3);
+        assert_eq!(ray.start(), (2, 3));
+        assert_eq!(
+            ray.cells().collect::<Vec<_>>(),
+            vec![(2, 3), (1, 2), (0, 1)]
+        );
+        assert_eq!(ray.clone().collect::<Vec<_>>(), b"MSM");
+        assert_eq!(
+            grid.ray(2, 3, Direction::LEFT).rev().collect::<Vec<_>>(),
+            b"ASXM"
+        );
+        assert_eq!(grid.ray(0, 0, Direction::LEFT).collect::<Vec<_>>(), b"X");
+    }
+
+    #[test]
+    fn test_rays_build_a_search() {
+        // Every line in four directions, read both ways, finds what find_word finds.
+        let grid = letter_soup(13, 21, 2);
+        let mut count = 0;
+        for dir in [
+            Direction::RIGHT,
+            Direction::DOWN,
+            Direction::DOWN_RIGHT,
+            Direction::DOWN_LEFT,
+        ] {
+            for ray in grid.rays(dir) {
+                let line: Vec<u8> = ray.collect();
+                count += line.windows(4).filter(|w| *w == b"XMAS").count();
+                count += line.windows(4).filter(|w| *w == b"SAMX").count();
+            }
+            let covered: usize = grid.rays(dir).map(|ray| ray.len()).sum();
+            assert_eq!(covered, 13 * 21);
+        }
+        assert_eq!(count, grid.count_word("XMAS"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Rendering a [`Grid`] with its matches highlighted.
+//!
+//! Reviewers can check a search by eye, either in a terminal with ANSI colours or in a
+//! self-contained HTML page holding an SVG drawing of the grid.
+
+use crate::Grid;
+use std::fmt::Write;
+
+// Terminal styles for matched and dimmed cells.
+const ANSI_MATCH: &str = "\x1b[1;33m";
+const ANSI_DIM: &str = "\x1b[2m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+// Size of one cell in the SVG drawing, in pixels.
+const SVG_CELL: usize = 20;
+
+/// A grid paired with the cells to highlight.
+/// The Elf takes out her highlighter pens before showing off her work.
+#[derive(Debug, Clone)]
+pub struct Highlighted<'g> {
+    grid: &'g Grid,
+    marked: Vec<bool>,
+    dim_unmatched: bool,
+}
+
+impl Grid {
+    /// Prepare to render the grid with the given `(row, col)` cells highlighted,
+    /// for example the cells of [`WordMatch`](crate::WordMatch)es or
+    /// [`StencilMatch`](crate::StencilMatch)es. Cells outside the grid are ignored.
+    pub fn highlighted<I>(&self, cells: I) -> Highlighted<'_>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut marked = vec![false; self.cells.len()];
+        for (row, col) in cells {
+            if row < self.height && col < self.width {
+                marked[row * self.width + col] = true;
+            }
+        }
+        Highlighted {
+            grid: self,
+            marked,
+            dim_unmatched: false,
+        }
+    }
+}
+
+impl Highlighted<'_> {
+    /// Fade the cells that are not part of any match.
+    pub fn dim_unmatched(mut self, dim: bool) -> Self {
+        self.dim_unmatched = dim;
+        self
+    }
+
+    /// Number of highlighted cells.
+    pub fn marked_count(&self) -> usize {
+        self.marked.iter().filter(|&&m| m).count()
+    }
+
+    fn is_marked(&self, row: usize, col: usize) -> bool {
+        self.marked[row * self.grid.width + col]
+    }
+
+    /// Render for a terminal, with matched cells in bold yellow.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::with_capacity(self.marked.len() * 4);
+        for row in 0..self.grid.height {
+            for col in 0..self.grid.width {
+                let letter = self.grid.get(row, col) as char;
+                if self.is_marked(row, col) {
+                    write!(out, "{ANSI_MATCH}{letter}{ANSI_RESET}").unwrap();
+                } else if self.dim_unmatched {
+                    write!(out, "{ANSI_DIM}{letter}{ANSI_RESET}").unwrap();
+                } else {
+                    out.push(letter);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Render as a standalone SVG drawing.
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (self.grid.width * SVG_CELL, self.grid.height * SVG_CELL);
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="14" text-anchor="middle">"#
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r##"<rect width="100%" height="100%" fill="#0f172a"/>"##
+        )
+        .unwrap();
+
+        for row in 0..self.grid.height {
+            for col in 0..self.grid.width {
+                let (x, y) = (col * SVG_CELL, row * SVG_CELL);
+                let fill = if self.is_marked(row, col) {
+                    writeln!(
+                        out,
+                        r##"<rect x="{x}" y="{y}" width="{SVG_CELL}" height="{SVG_CELL}" fill="#b45309"/>"##
+                    )
+                    .unwrap();
+                    "#fde047"
+                } else if self.dim_unmatched {
+                    "#475569"
+                } else {
+                    "#e2e8f0"
+                };
+                writeln!(
+                    out,
+                    r#"<text x="{}" y="{}" fill="{fill}">{}</text>"#,
+                    x + SVG_CELL / 2,
+                    y + SVG_CELL * 3 / 4,
+                    escape(self.grid.get(row, col) as char)
+                )
+                .unwrap();
+            }
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// Render as a self-contained HTML page embedding the SVG drawing.
+    pub fn to_html(&self, title: &str) -> String {
+        let title: String = title.chars().map(escape).collect();
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body {{ background: #020617; color: #e2e8f0; font-family: sans-serif; }}</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n<p>{} highlighted cells</p>\n{}</body>\n</html>\n",
+            self.marked_count(),
+            self.to_svg()
+        )
+    }
+}
+
+/// Escape a character for HTML and SVG text.
+fn escape(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        '\'' => "&#39;".to_string(),
+        _ => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_highlighting() {
+        let grid: Grid = "XMAS\n.<.&".parse().unwrap();
+        let matches = grid.find_word("XMAS");
+        let view = grid.highlighted(matches.iter().flat_map(|m| m.cells()));
+        assert_eq!(view.marked_count(), 4);
+
+        let ansi = view.to_ansi();
+        assert!(ansi.starts_with(&format!("{ANSI_MATCH}X{ANSI_RESET}")));
+        assert!(ansi.ends_with(".<.&\n"));
+
+        let dimmed = view.dim_unmatched(true).to_ansi();
+        assert!(dimmed.contains(&format!("{ANSI_DIM}<{ANSI_RESET}")));
+    }
+
+    #[test]
+    fn test_html_is_self_contained() {
+        let grid: Grid = "XMAS\n.<.&".parse().unwrap();
+        let html = grid.highlighted([(0, 0), (9, 9)]).to_html("XMAS <search>");
+        assert!(html.contains("<title>XMAS &lt;search&gt;</title>"));
+        assert!(html.contains("1 highlighted cells"));
+        assert!(html.contains(">&lt;</text>"));
+        assert!(html.contains(">&amp;</text>"));
+        assert_eq!(html.matches("<text").count(), 8);
+    }
+}
@@ -0,0 +1,417 @@
+//! Small 2D templates matched against a [`Grid`].
+//!
+//! A stencil is a rectangle of letters with wildcard cells that match anything. The
+//! "X-MAS" cross is one such stencil, and plus-shaped or L-shaped variants are just
+//! different templates.
+
+use crate::Grid;
+use std::fmt;
+use std::str::FromStr;
+
+/// Cell character that matches any letter when parsing a stencil.
+pub const WILDCARD: char = '.';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseStencilError {
+    /// The template has no rows or no columns.
+    Empty,
+    /// A row is wider or narrower than the first row.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for ParseStencilError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseStencilError::Empty => write!(f, "Stencil is empty - the Elf needs a shape!"),
+            ParseStencilError::RaggedRow {
+                row,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Stencil row {} has {} cells, expected {}",
+                row + 1,
+                actual,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseStencilError {}
+
+/// Which orientations of a stencil to search for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientations {
+    /// Only the stencil as written.
+    #[default]
+    Fixed,
+    /// The stencil and its quarter, half and three-quarter turns.
+    Rotations,
+    /// Every rotation, plus every rotation of the mirror image.
+    RotationsAndReflections,
+}
+
+/// How a stencil was turned before it matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Orientation {
+    /// Mirrored left-to-right before rotating.
+    pub mirrored: bool,
+    /// Clockwise quarter turns, 0 to 3.
+    pub quarter_turns: u8,
+}
+
+/// A rectangular template of letters and wildcards. Letters are bytes for a
+/// [`Grid`], or strings for a [`UnicodeGrid`](crate::UnicodeGrid).
+/// The Elf sketches the shape she is looking for on a scrap of paper.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stencil<T = u8> {
+    cells: Vec<Option<T>>,
+    height: usize,
+    width: usize,
+}
+
+impl FromStr for Stencil {
+    type Err = ParseStencilError;
+
+    /// Parse a stencil, one row per line, with `.` as the wildcard.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Stencil::from_rows(s.lines().map(|line| {
+            line.bytes()
+                .map(|byte| (byte != WILDCARD as u8).then_some(byte))
+        }))
+    }
+}
+
+impl Stencil {
+    /// The "X-MAS" cross: two diagonal "MAS" words sharing their 'A'.
+    /// Search it with [`Orientations::Rotations`] to find every reading direction.
+    pub fn x_mas() -> Stencil {
+        "M.S\n.A.\nM.S"
+            .parse()
+            .expect("X-MAS stencil is well formed")
+    }
+
+    /// The letter required at a cell, or `None` for a wildcard.
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        self.cells[row * self.width + col]
+    }
+}
+
+impl<T: Clone + Eq> Stencil<T> {
+    /// Build a stencil from its rows, `None` marking a wildcard.
+    pub(crate) fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Result<Self, ParseStencilError>
+    where
+        R: IntoIterator<Item = Option<T>>,
+    {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        for (row, line) in rows.into_iter().enumerate() {
+            let before = cells.len();
+            cells.extend(line);
+            let actual = cells.len() - before;
+            if row == 0 {
+                if actual == 0 {
+                    return Err(ParseStencilError::Empty);
+                }
+                width = actual;
+            } else if actual != width {
+                return Err(ParseStencilError::RaggedRow {
+                    row,
+                    expected: width,
+                    actual,
+                });
+            }
+        }
+        if width == 0 {
+            return Err(ParseStencilError::Empty);
+        }
+
+        Ok(Stencil {
+            height: cells.len() / width,
+            width,
+            cells,
+        })
+    }
+
+    /// Returns the dimensions of the stencil.
+    pub fn size(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    /// The letter required at a cell, or `None` for a wildcard.
+    fn cell(&self, row: usize, col: usize) -> Option<&T> {
+        self.cells[row * self.width + col].as_ref()
+    }
+
+    /// Replace every letter of the stencil, keeping its wildcards.
+    pub(crate) fn map<U>(&self, mut letter: impl FnMut(&T) -> U) -> Stencil<U> {
+        Stencil {
+            cells: self
+                .cells
+                .iter()
+                .map(|cell| cell.as_ref().map(&mut letter))
+                .collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Turn the stencil clockwise by a quarter.
+    pub fn rotated(&self) -> Stencil<T> {
+        let (height, width) = (self.width, self.height);
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| self.cell(self.height - 1 - col, row).cloned())
+            .collect();
+        Stencil {
+            cells,
+            height,
+            width,
+        }
+    }
+
+    /// Mirror the stencil left-to-right.
+    pub fn mirrored(&self) -> Stencil<T> {
+        let cells = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .map(|(row, col)| self.cell(row, self.width - 1 - col).cloned())
+            .collect();
+        Stencil {
+            cells,
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// The distinct orientations of the stencil, each with how it was produced.
+    /// Symmetric stencils yield fewer than the requested orientations, so a shape
+    /// that looks the same after a turn is never counted twice at one position.
+    pub fn variants(&self, orientations: Orientations) -> Vec<(Orientation, Stencil<T>)> {
+        let (mirrors, turns) = match orientations {
+            Orientations::Fixed => (1, 1),
+            Orientations::Rotations => (1, 4),
+            Orientations::RotationsAndReflections => (2, 4),
+        };
+
+        let mut variants: Vec<(Orientation, Stencil<T>)> = Vec::new();
+        for mirrored in [false, true].into_iter().take(mirrors) {
+            let mut stencil = if mirrored {
+                self.mirrored()
+            } else {
+                self.clone()
+            };
+            for quarter_turns in 0..turns {
+                if !variants.iter().any(|(_, seen)| *seen == stencil) {
+                    let orientation = Orientation {
+                        mirrored,
+                        quarter_turns,
+                    };
+                    variants.push((orientation, stencil.clone()));
+                }
+                stencil = stencil.rotated();
+            }
+        }
+        variants
+    }
+
+    /// The stencil's letters with their offsets, wildcards excluded.
+    pub(crate) fn letters(&self) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+        (0..self.height)
+            .flat_map(move |row| (0..self.width).map(move |col| (row, col)))
+            .filter_map(|(row, col)| Some((row, col, self.cell(row, col)?)))
+    }
+
+    /// Wrap a stencil taller or wider than a `height` by `width` torus onto it, so
+    /// that placements reading the same cells coincide. `None` if two letters of the
+    /// stencil land on one cell and disagree, so it can never match.
+    pub(crate) fn folded(&self, height: usize, width: usize) -> Option<Stencil<T>> {
+        let (folded_height, folded_width) = (self.height.min(height), self.width.min(width));
+        let mut cells = vec![None; folded_height * folded_width];
+        for (row, col, letter) in self.letters() {
+            let cell = &mut cells[(row % folded_height) * folded_width + col % folded_width];
+            match cell {
+                Some(existing) if existing != letter => return None,
+                _ => *cell = Some(letter.clone()),
+            }
+        }
+        Some(Stencil {
+            cells,
+            height: folded_height,
+            width: folded_width,
+        })
+    }
+
+    /// Whether the stencil matches rows of `width` cells with its top-left corner
+    /// at `(row, col)`.
+    fn matches_at(&self, cells: &[T], width: usize, row: usize, col: usize) -> bool {
+        self.letters()
+            .all(|(r, c, letter)| cells[(row + r) * width + col + c] == *letter)
+    }
+
+    /// Top-left corners where the stencil fits inside a grid of the given size.
+    fn positions(&self, height: usize, width: usize) -> impl Iterator<Item = (usize, usize)> {
+        let rows = (height + 1).saturating_sub(self.height);
+        let cols = (width + 1).saturating_sub(self.width);
+        (0..rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    /// Every placement of the stencil in rows of `width` cells, in the requested orientations.
+    pub(crate) fn find_in(
+        &self,
+        cells: &[T],
+        width: usize,
+        orientations: Orientations,
+    ) -> Vec<StencilMatch> {
+        let height = cells.len() / width;
+        let mut matches = Vec::new();
+        for (orientation, variant) in self.variants(orientations) {
+            for (row, col) in variant.positions(height, width) {
+                if variant.matches_at(cells, width, row, col) {
+                    let cells = variant
+                        .letters()
+                        .map(|(r, c, _)| (row + r, col + c))
+                        .collect();
+                    matches.push(StencilMatch {
+                        row,
+                        col,
+                        orientation,
+                        cells,
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    /// Count the placements of the stencil in rows of `width` cells.
+    pub(crate) fn count_in(&self, cells: &[T], width: usize, orientations: Orientations) -> usize {
+        let height = cells.len() / width;
+        self.variants(orientations)
+            .iter()
+            .map(|(_, variant)| {
+                variant
+                    .positions(height, width)
+                    .filter(|&(row, col)| variant.matches_at(cells, width, row, col))
+                    .count()
+            })
+            .sum()
+    }
+}
+
+impl fmt::Display for Stencil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.map(|&letter| char::from(letter).to_string()))
+    }
+}
+
+impl fmt::Display for Stencil<String> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                match self.cell(row, col) {
+                    Some(letter) => write!(f, "{}", letter)?,
+                    None => write!(f, "{}", WILDCARD)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A stencil found in the grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StencilMatch {
+    /// Top-left corner of the oriented stencil.
+    pub row: usize,
+    pub col: usize,
+    pub orientation: Orientation,
+    /// Grid cells covered by the stencil's letters, wildcards excluded.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Grid {
+    /// Find every placement of `stencil` in the requested orientations.
+    /// The Elf slides her paper shape across the puzzle, turning it as she goes.
+    pub fn find_stencil(&self, stencil: &Stencil, orientations: Orientations) -> Vec<StencilMatch> {
+        stencil.find_in(&self.cells, self.width, orientations)
+    }
+
+    /// Count the placements of `stencil` in the requested orientations.
+    pub fn count_stencil(&self, stencil: &Stencil, orientations: Orientations) -> usize {
+        stencil.count_in(&self.cells, self.width, orientations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x_mas_variants() {
+        let x_mas = Stencil::x_mas();
+        assert_eq!(x_mas.variants(Orientations::Fixed).len(), 1);
+        assert_eq!(x_mas.variants(Orientations::Rotations).len(), 4);
+        // Mirroring the cross gives one of its rotations, so nothing new appears.
+        assert_eq!(
+            x_mas.variants(Orientations::RotationsAndReflections).len(),
+            4
+        );
+        assert_eq!(x_mas.rotated().to_string(), "M.M\n.A.\nS.S\n");
+    }
+
+    #[test]
+    fn test_l_shape_orientations() {
+        let l_shape: Stencil = "X.\nM.\nAS".parse().unwrap();
+        assert_eq!(l_shape.variants(Orientations::Rotations).len(), 4);
+        assert_eq!(
+            l_shape
+                .variants(Orientations::RotationsAndReflections)
+                .len(),
+            8
+        );
+
+        // Draw the mirrored, quarter-turned L into a grid of its own.
+        let turned = l_shape.mirrored().rotated();
+        let grid: Grid = turned.to_string().parse().unwrap();
+        assert_eq!(grid.count_stencil(&l_shape, Orientations::Fixed), 0);
+        assert_eq!(grid.count_stencil(&l_shape, Orientations::Rotations), 0);
+
+        let found = grid.find_stencil(&l_shape, Orientations::RotationsAndReflections);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].orientation,
+            Orientation {
+                mirrored: true,
+                quarter_turns: 1,
+            }
+        );
+        assert_eq!(found[0].cells.len(), 4);
+    }
+
+    #[test]
+    fn test_plus_shape() {
+        let plus: Stencil = ".M.\nMAS\n.S.".parse().unwrap();
+        let grid: Grid = ".M.\nMAS\n.S.".parse().unwrap();
+        assert_eq!(grid.count_stencil(&plus, Orientations::Fixed), 1);
+        assert_eq!(grid.count_stencil(&plus, Orientations::Rotations), 1);
+    }
+
+    #[test]
+    fn test_ragged_stencil() {
+        assert_eq!(
+            "M.S\n.A".parse::<Stencil>(),
+            Err(ParseStencilError::RaggedRow {
+                row: 1,
+                expected: 3,
+                actual: 2,
+            })
+        );
+        assert_eq!("".parse::<Stencil>(), Err(ParseStencilError::Empty));
+    }
+}
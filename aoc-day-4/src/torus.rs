@@ -0,0 +1,265 @@
+//! Wrap-around searches, treating the [`Grid`] as a torus.
+//!
+//! Reading off the right edge continues from the left edge, and reading off the bottom
+//! continues from the top. Matches report wrapped coordinates. When a word or stencil is
+//! longer than the grid in some dimension, directions or orientations that read exactly
+//! the same cells are counted once.
+
+use crate::{Direction, Grid, Orientations, Stencil, StencilMatch, WordMatch};
+
+/// A grid whose edges wrap around.
+/// The Elf rolls her puzzle into a tube, then bends the tube into a ring.
+#[derive(Debug, Clone, Copy)]
+pub struct Torus<'g> {
+    grid: &'g Grid,
+}
+
+impl Grid {
+    /// Search this grid with its edges wrapping around.
+    pub fn torus(&self) -> Torus<'_> {
+        Torus { grid: self }
+    }
+}
+
+impl<'g> Torus<'g> {
+    /// Wrap a position that may have stepped off the grid back onto it.
+    fn wrap(&self, row: isize, col: isize) -> (usize, usize) {
+        (
+            row.rem_euclid(self.grid.height as isize) as usize,
+            col.rem_euclid(self.grid.width as isize) as usize,
+        )
+    }
+
+    /// The directions that read different cells on this grid, in [`Direction::ALL`]
+    /// order. A match is its start and its wrapped step, so directions sharing a
+    /// step are one direction: on a grid two rows tall, stepping up and stepping
+    /// down meet the same cells. On a grid one row tall, stepping up wraps back to
+    /// the same cell, and reads a letter over and over.
+    fn distinct_directions(&self) -> Vec<Direction> {
+        let mut steps = Vec::new();
+        let mut directions = Vec::new();
+        for dir in Direction::ALL {
+            let step = self.wrap(dir.dy as isize, dir.dx as isize);
+            if !steps.contains(&step) {
+                steps.push(step);
+                directions.push(dir);
+            }
+        }
+        directions
+    }
+
+    /// Find every occurrence of `word` in any direction, wrapping around the edges.
+    /// Matches start at wrapped coordinates, see [`Torus::cells`] for their letters.
+    pub fn find_word(&self, word: &str) -> Vec<WordMatch> {
+        let word = word.as_bytes();
+        let Some((&first, rest)) = word.split_first() else {
+            return Vec::new();
+        };
+        let directions = if rest.is_empty() {
+            vec![Direction::RIGHT]
+        } else {
+            self.distinct_directions()
+        };
+        let mut matches = Vec::new();
+
+        for (pos, _) in self
+            .grid
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c == first)
+        {
+            let (row, col) = (pos / self.grid.width, pos % self.grid.width);
+
+            for &dir in &directions {
+                let found = rest.iter().enumerate().all(|(i, &letter)| {
+                    let step = i as isize + 1;
+                    let (r, c) = self.wrap(
+                        row as isize + dir.dy as isize * step,
+                        col as isize + dir.dx as isize * step,
+                    );
+                    self.grid.get(r, c) == letter
+                });
+
+                if found {
+                    matches.push(WordMatch {
+                        row,
+                        col,
+                        direction: dir,
+                        len: word.len(),
+                    });
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Count the occurrences of `word` in any direction, wrapping around the edges.
+    pub fn count_word(&self, word: &str) -> usize {
+        self.find_word(word).len()
+    }
+
+    /// The wrapped `(row, col)` of every letter in a match, in reading order.
+    pub fn cells(&self, found: &WordMatch) -> impl Iterator<Item = (usize, usize)> + 'g {
+        let (torus, found) = (*self, *found);
+        (0..found.len).map(move |step| {
+            torus.wrap(
+                found.row as isize + found.direction.dy as isize * step as isize,
+                found.col as isize + found.direction.dx as isize * step as isize,
+            )
+        })
+    }
+
+    /// Find every placement of `stencil` in the requested orientations, with every
+    /// cell of the grid as a top-left corner.
+    pub fn find_stencil(&self, stencil: &Stencil, orientations: Orientations) -> Vec<StencilMatch> {
+        let (height, width) = self.grid.size();
+
+        // Orientations that fold onto the same cells are one and the same search.
+        let mut variants: Vec<_> = Vec::new();
+        for (orientation, variant) in stencil.variants(orientations) {
+            if let Some(folded) = variant.folded(height, width) {
+                if !variants.iter().any(|(_, seen)| *seen == folded) {
+                    variants.push((orientation, folded));
+                }
+            }
+        }
+
+        let mut matches = Vec::new();
+        for (orientation, variant) in &variants {
+            let letters: Vec<_> = variant.letters().collect();
+            for (row, col) in (0..height).flat_map(|row| (0..width).map(move |col| (row, col))) {
+                let cells: Vec<_> = letters
+                    .iter()
+                    .map(|&(r, c, _)| self.wrap((row + r) as isize, (col + c) as isize))
+                    .collect();
+                let found = cells
+                    .iter()
+                    .zip(&letters)
+                    .all(|(&(r, c), &(_, _, letter))| self.grid.get(r, c) == *letter);
+
+                if found {
+                    matches.push(StencilMatch {
+                        row,
+                        col,
+                        orientation: *orientation,
+                        cells,
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    /// Count the placements of `stencil` in the requested orientations.
+    pub fn count_stencil(&self, stencil: &Stencil, orientations: Orientations) -> usize {
+        self.find_stencil(stencil, orientations).len()
+    }
+
+    /// Count the "X-MAS" crosses, wrapping around the edges.
+    pub fn find_crossed_mas(&self) -> usize {
+        self.count_stencil(&Stencil::x_mas(), Orientations::Rotations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::letter_soup;
+
+    #[test]
+    fn test_wrapped_words() {
+        // "XMAS" only reads across the seams.
+        let grid: Grid = "ASXM\n...A\n...S\n...X".parse().unwrap();
+        assert_eq!(grid.count_word("XMAS"), 0);
+
+        let torus = grid.torus();
+        let found = torus.find_word("XMAS");
+        assert!(found.contains(&WordMatch {
+            row: 0,
+            col: 2,
+            direction: Direction::RIGHT,
+            len: 4,
+        }));
+        let down = found
+            .iter()
+            .find(|m| (m.row, m.col, m.direction) == (3, 3, Direction::DOWN))
+            .unwrap();
+        assert_eq!(
+            torus.cells(down).collect::<Vec<_>>(),
+            vec![(3, 3), (0, 3), (1, 3), (2, 3)]
+        );
+    }
+
+    #[test]
+    fn test_words_longer_than_the_grid() {
+        // On a single row, every vertical or diagonal step is a horizontal one.
+        let row: Grid = "XMAS".parse().unwrap();
+        let torus = row.torus();
+        assert_eq!(torus.distinct_directions().len(), 3);
+        assert_eq!(torus.count_word("XMASXMAS"), 1);
+        assert_eq!(torus.count_word("SAMXSAMX"), 1);
+        assert_eq!(torus.count_word("X"), 1);
+
+        // Two rows tall: up and down meet the same cells.
+        let grid: Grid = "XM\nMX".parse().unwrap();
+        assert_eq!(grid.torus().distinct_directions().len(), 3);
+        assert_eq!(grid.torus().count_word("XMXMX"), 4);
+    }
+
+    #[test]
+    fn test_repeated_letters_on_thin_grids() {
+        // A 1x1 torus reads its one letter forever, once per start.
+        let dot: Grid = "A".parse().unwrap();
+        assert_eq!(dot.torus().count_word("AA"), 1);
+        assert_eq!(dot.torus().count_word("AAAAA"), 1);
+        assert_eq!(dot.torus().count_word("AB"), 0);
+
+        // On a 1xN row, stepping up or down stays on the same cell.
+        let row: Grid = "XMAS".parse().unwrap();
+        let found = row.torus().find_word("SS");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            row.torus().cells(&found[0]).collect::<Vec<_>>(),
+            vec![(0, 3), (0, 3)]
+        );
+        assert_eq!(row.torus().count_word("AA"), 1);
+
+        // And on an Nx1 column, stepping left or right does.
+        let column: Grid = "X\nM\nA\nS".parse().unwrap();
+        assert_eq!(column.torus().count_word("MM"), 1);
+        assert_eq!(column.torus().count_word("XMASXMAS"), 1);
+    }
+
+    #[test]
+    fn test_wrapped_stencils() {
+        // The cross straddles the corner of the grid.
+        let grid: Grid = "A...\n.S.M\n....\n.S.M".parse().unwrap();
+        assert_eq!(grid.find_crossed_mas(), 0);
+        let found = grid
+            .torus()
+            .find_stencil(&Stencil::x_mas(), Orientations::Rotations);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].cells.contains(&(0, 0)));
+        assert!(found[0].cells.contains(&(3, 3)));
+
+        // The torus finds everything the bounded search does, and more.
+        let soup = letter_soup(17, 23, 3);
+        assert!(soup.torus().find_crossed_mas() >= soup.find_crossed_mas());
+        assert!(soup.torus().count_word("XMAS") >= soup.count_word("XMAS"));
+    }
+
+    #[test]
+    fn test_stencil_larger_than_the_grid() {
+        // Folded onto a 1x1 grid, every letter of the stencil lands on one cell.
+        let grid: Grid = "A".parse().unwrap();
+        assert_eq!(grid.torus().find_crossed_mas(), 0);
+        let dots: Stencil = "A.A\n...\nA.A".parse().unwrap();
+        assert_eq!(
+            grid.torus()
+                .count_stencil(&dots, Orientations::RotationsAndReflections),
+            1
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Grids written in any script.
+//!
+//! A [`Grid`](crate::Grid) holds one byte per cell, which only fits ASCII letters. A
+//! [`UnicodeGrid`] splits its rows into characters or grapheme clusters instead, so
+//! Cyrillic, Greek or accented puzzles are measured and indexed by what a reader sees.
+//! Each distinct cell is numbered once, and the searches run over those numbers.
+
+use crate::{
+    find_word_in, grid_lines, Orientations, ParseGridError, ParseMode, ParseStencilError, Stencil,
+    StencilMatch, WordMatch, WILDCARD,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a single cell of a [`UnicodeGrid`] holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Segmentation {
+    /// One Unicode scalar value per cell.
+    Chars,
+    /// One extended grapheme cluster per cell, so a letter and its combining accents
+    /// share a cell.
+    #[default]
+    Graphemes,
+}
+
+impl Segmentation {
+    /// Split a line of text into cells.
+    fn split(self, line: &str) -> Vec<&str> {
+        match self {
+            Segmentation::Chars => line
+                .char_indices()
+                .map(|(at, c)| &line[at..at + c.len_utf8()])
+                .collect(),
+            Segmentation::Graphemes => line.graphemes(true).collect(),
+        }
+    }
+}
+
+/// A word search grid whose letters may take several bytes each.
+/// The Elf's pen pals send puzzles from all over the world.
+#[derive(Debug, Clone)]
+pub struct UnicodeGrid {
+    cells: Vec<u32>,
+    symbols: Vec<String>,
+    index: HashMap<String, u32>,
+    height: usize,
+    width: usize,
+    segmentation: Segmentation,
+}
+
+impl FromStr for UnicodeGrid {
+    type Err = ParseGridError;
+
+    /// Parse a grid of grapheme clusters in [`ParseMode::Strict`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnicodeGrid::parse_with(s, Segmentation::Graphemes, ParseMode::Strict)
+    }
+}
+
+impl UnicodeGrid {
+    /// Parse a grid, one row per line, every row as many cells wide as the first.
+    pub fn parse_with(
+        s: &str,
+        segmentation: Segmentation,
+        mode: ParseMode,
+    ) -> Result<UnicodeGrid, ParseGridError> {
+        let mut grid = UnicodeGrid {
+            cells: Vec::new(),
+            symbols: Vec::new(),
+            index: HashMap::new(),
+            height: 0,
+            width: 0,
+            segmentation,
+        };
+
+        for (row, line) in grid_lines(s, mode).enumerate() {
+            let cells = segmentation.split(line);
+            if row == 0 {
+                grid.width = cells.len();
+            }
+            if grid.width == 0 {
+                return Err(ParseGridError::Empty);
+            }
+
+            for (col, cell) in cells.iter().enumerate() {
+                if let Some(found) = cell
+                    .chars()
+                    .next()
+                    .filter(|c| c.is_whitespace() || c.is_control())
+                {
+                    return Err(ParseGridError::InvalidChar { row, col, found });
+                }
+            }
+            if cells.len() != grid.width {
+                return Err(ParseGridError::RaggedRow {
+                    row,
+                    expected: grid.width,
+                    actual: cells.len(),
+                });
+            }
+
+            for cell in cells {
+                let symbol = grid.intern(cell);
+                grid.cells.push(symbol);
+            }
+            grid.height += 1;
+        }
+        if grid.height == 0 {
+            return Err(ParseGridError::Empty);
+        }
+
+        Ok(grid)
+    }
+
+    /// Number a cell's text, reusing the number of an identical cell.
+    fn intern(&mut self, cell: &str) -> u32 {
+        if let Some(&symbol) = self.index.get(cell) {
+            return symbol;
+        }
+        let symbol = self.symbols.len() as u32;
+        self.symbols.push(cell.to_string());
+        self.index.insert(cell.to_string(), symbol);
+        symbol
+    }
+
+    /// Returns the dimensions of the grid, in cells.
+    pub fn size(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    /// How the grid's rows were split into cells.
+    pub fn segmentation(&self) -> Segmentation {
+        self.segmentation
+    }
+
+    /// The text of the cell at `(row, col)`.
+    pub fn get(&self, row: usize, col: usize) -> &str {
+        &self.symbols[self.cells[row * self.width + col] as usize]
+    }
+
+    /// Find every occurrence of `word`, split into cells like the grid's rows.
+    /// A word with a letter that appears nowhere in the grid has no matches.
+    pub fn find_word(&self, word: &str) -> Vec<WordMatch> {
+        let symbols: Option<Vec<u32>> = self
+            .segmentation
+            .split(word)
+            .into_iter()
+            .map(|cell| self.index.get(cell).copied())
+            .collect();
+
+        match symbols {
+            Some(symbols) => find_word_in(&self.cells, self.width, &symbols, 0..self.height),
+            None => Vec::new(),
+        }
+    }
+
+    /// Count the occurrences of `word` in any of the eight directions.
+    pub fn count_word(&self, word: &str) -> usize {
+        self.find_word(word).len()
+    }
+
+    /// The stencil with its letters numbered like the grid's cells, or `None` if it
+    /// holds a letter that appears nowhere in the grid.
+    fn numbered(&self, stencil: &Stencil<String>) -> Option<Stencil<u32>> {
+        if stencil
+            .letters()
+            .any(|(_, _, letter)| !self.index.contains_key(letter))
+        {
+            return None;
+        }
+        Some(stencil.map(|letter| self.index[letter]))
+    }
+
+    /// Find every placement of `stencil` in the requested orientations.
+    pub fn find_stencil(
+        &self,
+        stencil: &Stencil<String>,
+        orientations: Orientations,
+    ) -> Vec<StencilMatch> {
+        self.numbered(stencil).map_or_else(Vec::new, |stencil| {
+            stencil.find_in(&self.cells, self.width, orientations)
+        })
+    }
+
+    /// Count the placements of `stencil` in the requested orientations.
+    pub fn count_stencil(&self, stencil: &Stencil<String>, orientations: Orientations) -> usize {
+        self.numbered(stencil).map_or(0, |stencil| {
+            stencil.count_in(&self.cells, self.width, orientations)
+        })
+    }
+
+    /// Count the "X-MAS" crosses, for puzzles that mix them with other scripts.
+    pub fn find_crossed_mas(&self) -> usize {
+        let x_mas = Stencil::x_mas().map(|&letter| char::from(letter).to_string());
+        self.count_stencil(&x_mas, Orientations::Rotations)
+    }
+}
+
+impl Stencil<String> {
+    /// Parse a stencil for a [`UnicodeGrid`], one row per line, with `.` as the wildcard.
+    pub fn parse_unicode(s: &str, segmentation: Segmentation) -> Result<Self, ParseStencilError> {
+        let wildcard = WILDCARD.to_string();
+        Stencil::from_rows(s.lines().map(|line| {
+            segmentation
+                .split(line)
+                .into_iter()
+                .map(|cell| (cell != wildcard).then(|| cell.to_string()))
+                .collect::<Vec<_>>()
+        }))
+    }
+}
+
+impl fmt::Display for UnicodeGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                write!(f, "{}", self.get(row, col))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::letter_soup;
+    use crate::Direction;
+
+    #[test]
+    fn test_cyrillic_words() {
+        // "ЁЛКА", the Russian for a Christmas tree, across and down.
+        let grid: UnicodeGrid = "ЁЛКАЖ\nЛЖЖЖЖ\nКЖЖЖЖ\nАЖЖЖЖ".parse().unwrap();
+        assert_eq!(grid.size(), (4, 5));
+        assert_eq!(grid.get(0, 3), "А");
+
+        let found = grid.find_word("ЁЛКА");
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().any(|m| m.direction == Direction::RIGHT));
+        assert!(found.iter().any(|m| m.direction == Direction::DOWN));
+        assert_eq!(grid.count_word("АКЛЁ"), 2);
+        assert_eq!(grid.count_word("ЁЛКАЯ"), 0);
+
+        // The byte grid refuses the same text and says where it stumbled.
+        assert_eq!(
+            "ЁЛКАЖ".parse::<crate::Grid>().unwrap_err(),
+            ParseGridError::InvalidChar {
+                row: 0,
+                col: 0,
+                found: 'Ё',
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        // Like the byte grid, an empty puzzle is refused rather than searched.
+        for text in ["", "\n", "\n\n"] {
+            assert_eq!(
+                text.parse::<UnicodeGrid>().unwrap_err(),
+                ParseGridError::Empty
+            );
+        }
+        assert_eq!(
+            UnicodeGrid::parse_with("", Segmentation::Chars, ParseMode::Lenient).unwrap_err(),
+            ParseGridError::Empty
+        );
+    }
+
+    #[test]
+    fn test_graphemes_and_chars() {
+        // The first cell is 'e' followed by a combining acute accent.
+        let text = "e\u{301}té\nété";
+        let grid =
+            UnicodeGrid::parse_with(text, Segmentation::Graphemes, ParseMode::Strict).unwrap();
+        assert_eq!(grid.size(), (2, 3));
+        assert_eq!(grid.count_word("e\u{301}t"), 2);
+        // Decomposed and precomposed accents look alike but are different cells.
+        assert_ne!(grid.get(0, 0), grid.get(1, 0));
+
+        // Split by chars, the accent takes a cell of its own and the rows disagree.
+        assert_eq!(
+            UnicodeGrid::parse_with(text, Segmentation::Chars, ParseMode::Strict).unwrap_err(),
+            ParseGridError::RaggedRow {
+                row: 1,
+                expected: 4,
+                actual: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_greek_stencil() {
+        let grid: UnicodeGrid = "Μ.Σ\n.Α.\nΜ.Σ".parse().unwrap();
+        let cross = Stencil::parse_unicode("Μ.Σ\n.Α.\nΜ.Σ", Segmentation::Graphemes).unwrap();
+        assert_eq!(cross.size(), (3, 3));
+        assert_eq!(grid.count_stencil(&cross, Orientations::Rotations), 1);
+        assert_eq!(cross.rotated().to_string(), "Μ.Μ\n.Α.\nΣ.Σ\n");
+
+        let missing = Stencil::parse_unicode("Ω", Segmentation::Graphemes).unwrap();
+        assert!(grid.find_stencil(&missing, Orientations::Fixed).is_empty());
+    }
+
+    #[test]
+    fn test_ascii_agrees_with_byte_grid() {
+        let grid = letter_soup(19, 27, 8);
+        let unicode: UnicodeGrid = grid.to_string().parse().unwrap();
+        assert_eq!(unicode.to_string(), grid.to_string());
+        for word in ["XMAS", "SAM", "A", "XMASX"] {
+            assert_eq!(unicode.find_word(word), grid.find_word(word));
+        }
+        assert_eq!(unicode.find_crossed_mas(), grid.find_crossed_mas());
+    }
+}
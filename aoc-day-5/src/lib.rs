@@ -0,0 +1,702 @@
+mod orderings;
+mod repair;
+
+pub use orderings::SacredOrderings;
+pub use repair::{MovedPage, PageRepair};
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+/// The sacred rules of the Enchanted Printer, dictating the mystical order
+/// in which safety manual pages must materialize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrinterLore {
+    pub prerequisite: u32, // The page that must be printed first
+    pub dependent: u32,    // The page that must follow
+}
+
+/// The printer's verdict on a whole scroll of updates
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PrinterDiagnosis {
+    pub properly_ordered_sum: u32, // Sum of middle pages from correctly ordered updates
+    pub reordered_pages_sum: u32,  // Sum of middle pages after fixing incorrect orders
+    pub warnings: Vec<CycleError>, // Cycles printed as queued in lenient mode
+}
+
+impl fmt::Display for PrinterDiagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redact = f.sign_minus(); // Using sign_minus as our redaction flag
+
+        writeln!(f, "🖨️  Ancient Printer Diagnosis Complete! 📜")?;
+        writeln!(f, "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━")?;
+        write!(f, "✨ Properly Ordered Updates Sum: ")?;
+        if redact {
+            writeln!(f, "*****")?;
+        } else {
+            writeln!(f, "{}", self.properly_ordered_sum)?;
+        }
+        write!(f, "📝 Reordered Updates Sum: ")?;
+        if redact {
+            writeln!(f, "*****")?;
+        } else {
+            writeln!(f, "{}", self.reordered_pages_sum)?;
+        }
+        writeln!(f, "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━")
+    }
+}
+
+impl fmt::Display for PrinterLore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.prerequisite, self.dependent)
+    }
+}
+
+/// How the printer copes when the sacred rules chase their own tails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CycleMode {
+    /// Refuse to arrange an update whose pages are bound in a cycle
+    Strict,
+    /// Warn about the cycle, then print its pages in their original order
+    #[default]
+    Lenient,
+}
+
+/// Rules that bind a ring of pages so that each must precede the next,
+/// leaving no way to print any of them first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    pub pages: Vec<u32>,        // The pages around the cycle, in rule order
+    pub lore: Vec<PrinterLore>, // The rules binding each page to the next, then the last to the first
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pages ")?;
+        for page in &self.pages {
+            write!(f, "{} → ", page)?;
+        }
+        write!(f, "{} form a cycle under rules ", self.pages[0])?;
+        for (i, rule) in self.lore.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            write!(f, "{}{}", separator, rule)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Two pages of an update printed against the sacred order: the later page
+/// was bound by a rule to come first. Positions are stored as 0-based indices,
+/// but [`Display`](fmt::Display) counts them from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderViolation {
+    pub early_page: u32,          // The page printed too soon
+    pub early_position: usize,    // Its 0-based index in the update
+    pub late_page: u32,           // The page printed too late
+    pub late_position: usize,     // Its 0-based index in the update
+    pub broken_rule: PrinterLore, // The rule demanding the late page come first
+}
+
+impl fmt::Display for OrderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "page {} at position {} precedes page {} at position {}, breaking rule {}",
+            self.early_page,
+            self.early_position + 1,
+            self.late_page,
+            self.late_position + 1,
+            self.broken_rule
+        )
+    }
+}
+
+impl PrinterLore {
+    /// Decipher a line of printer lore from the ancient maintenance manual
+    pub fn decipher(ancient_text: &str) -> Option<Self> {
+        let mut runes = ancient_text.split('|');
+        let prerequisite = runes.next()?.parse().ok()?;
+        let dependent = runes.next()?.parse().ok()?;
+        Some(Self {
+            prerequisite,
+            dependent,
+        })
+    }
+}
+
+/// Interpret the ancient printer's maintenance manual
+pub fn interpret_printer_lore(sacred_text: &str) -> Vec<PrinterLore> {
+    sacred_text
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(PrinterLore::decipher)
+        .collect()
+}
+
+/// Decode the list of pending manual updates from the printer's enchanted queue
+pub fn decode_update_requests(enchanted_queue: &str) -> Vec<Vec<u32>> {
+    enchanted_queue
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| line.split(',').map(|num| num.trim().parse().ok()).collect())
+        .collect()
+}
+
+/// The Printer's Tome of Dependencies - a mystical record of which pages
+/// must precede others in the grand tapestry of safety documentation
+///
+/// Inscribed once per scroll, the tome numbers every page named by a rule and
+/// keeps a dense bit matrix over those numbers, so asking whether one page must
+/// precede another is a lookup and a single bit test.
+#[derive(Debug, Default, Clone)]
+pub struct PrinterTome {
+    slots: HashMap<u32, usize>, // Each page named by a rule, numbered densely
+    stride: usize,              // Words per row of the matrix
+    sacred_order: Vec<u64>,     // Bit (a, b) is set when page a must precede page b
+}
+
+impl PrinterTome {
+    /// Construct the tome from the ancient printer lore
+    pub fn inscribe(lore: &[PrinterLore]) -> Self {
+        let mut slots = HashMap::new();
+        for rule in lore {
+            for page in [rule.prerequisite, rule.dependent] {
+                let next = slots.len();
+                slots.entry(page).or_insert(next);
+            }
+        }
+
+        let stride = slots.len().div_ceil(64);
+        let mut tome = Self {
+            sacred_order: vec![0; slots.len() * stride],
+            slots,
+            stride,
+        };
+        for rule in lore {
+            let (first, second) = (tome.slots[&rule.prerequisite], tome.slots[&rule.dependent]);
+            tome.sacred_order[first * tome.stride + second / 64] |= 1 << (second % 64);
+        }
+        tome
+    }
+
+    /// The number of distinct pages named by the lore
+    pub fn page_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The page's row in the matrix, if any rule names it
+    fn slot(&self, page: u32) -> Option<usize> {
+        self.slots.get(&page).copied()
+    }
+
+    /// Whether the page in row `first` must precede the page in row `second`
+    fn binds(&self, first: usize, second: usize) -> bool {
+        self.sacred_order[first * self.stride + second / 64] & (1 << (second % 64)) != 0
+    }
+
+    /// Whether a rule demands that `first_page` be printed before `second_page`
+    pub fn must_precede(&self, first_page: u32, second_page: u32) -> bool {
+        match (self.slot(first_page), self.slot(second_page)) {
+            (Some(first), Some(second)) => self.binds(first, second),
+            _ => false,
+        }
+    }
+
+    /// Consult the tome to check if a page ordering violates the sacred rules
+    pub fn violates_sacred_order(&self, first_page: u32, second_page: u32) -> bool {
+        self.must_precede(second_page, first_page)
+    }
+}
+
+/// Verify if a sequence of pages respects the sacred printing order, for
+/// every pair of pages and not just neighbours
+///
+/// The pages already printed are gathered in a bitset over the tome's rows, so
+/// each page is checked against all of its predecessors at once by intersecting
+/// that bitset with the page's row of the matrix.
+pub fn respects_sacred_order(update: &[u32], tome: &PrinterTome) -> bool {
+    let mut printed = vec![0u64; tome.stride];
+    for &page in update {
+        let Some(slot) = tome.slot(page) else {
+            continue;
+        };
+        let row = &tome.sacred_order[slot * tome.stride..(slot + 1) * tome.stride];
+        if row
+            .iter()
+            .zip(&printed)
+            .any(|(must_follow, printed)| must_follow & printed != 0)
+        {
+            return false;
+        }
+        printed[slot / 64] |= 1 << (slot % 64);
+    }
+    true
+}
+
+/// List every pair of pages in an update printed against the sacred order,
+/// by the position of the earlier page and then of the later one
+pub fn find_violations(update: &[u32], tome: &PrinterTome) -> Vec<OrderViolation> {
+    let slots: Vec<_> = update.iter().map(|&page| tome.slot(page)).collect();
+    let mut violations = Vec::new();
+
+    for (early_position, early_slot) in slots.iter().enumerate() {
+        let Some(early_slot) = *early_slot else {
+            continue;
+        };
+        for (late_position, late_slot) in slots.iter().enumerate().skip(early_position + 1) {
+            if late_slot.is_some_and(|late_slot| tome.binds(late_slot, early_slot)) {
+                let (early_page, late_page) = (update[early_position], update[late_position]);
+                violations.push(OrderViolation {
+                    early_page,
+                    early_position,
+                    late_page,
+                    late_position,
+                    broken_rule: PrinterLore {
+                        prerequisite: late_page,
+                        dependent: early_page,
+                    },
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Find the mystical middle page number of an update sequence
+///
+/// # Panics
+/// If the update holds no pages at all.
+pub fn divine_middle_page(update: &[u32]) -> u32 {
+    update[update.len() / 2]
+}
+
+/// Which ready page the Order Keeper prints first when the rules allow several
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// The page that came earliest in the original update
+    #[default]
+    OriginalPosition,
+    /// The page with the smallest number, then the earliest of equal pages
+    SmallestPage,
+}
+
+/// The Enchanted Order Keeper - responsible for arranging pages
+/// in accordance with the sacred printing laws
+#[derive(Debug, Clone)]
+pub struct EnchantedOrderKeeper {
+    pages: Vec<u32>,                 // The update's pages, in their original order
+    dependents: Vec<Vec<usize>>,     // Positions of the pages that must follow each page
+    prerequisites_count: Vec<usize>, // Pages that must precede each page
+    tie_break: TieBreak,             // Which ready page goes first
+}
+
+impl EnchantedOrderKeeper {
+    /// Gather the rules that bind the pages of a single update
+    pub fn summon(pages: &[u32], tome: &PrinterTome) -> Self {
+        let slots: Vec<_> = pages.iter().map(|&page| tome.slot(page)).collect();
+        let mut dependents = vec![Vec::new(); pages.len()];
+        let mut prerequisites_count = vec![0; pages.len()];
+
+        for (first, first_slot) in slots.iter().enumerate() {
+            let Some(first_slot) = *first_slot else {
+                continue;
+            };
+            for (second, second_slot) in slots.iter().enumerate() {
+                if second_slot.is_some_and(|second_slot| tome.binds(first_slot, second_slot)) {
+                    dependents[first].push(second);
+                    prerequisites_count[second] += 1;
+                }
+            }
+        }
+
+        Self {
+            pages: pages.to_vec(),
+            dependents,
+            prerequisites_count,
+            tie_break: TieBreak::default(),
+        }
+    }
+
+    /// Choose which ready page goes first when the rules allow several
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// The key ready pages are printed by, smallest first
+    fn precedence(&self, at: usize) -> (u32, usize) {
+        match self.tie_break {
+            TieBreak::OriginalPosition => (0, at),
+            TieBreak::SmallestPage => (self.pages[at], at),
+        }
+    }
+
+    /// Arrange pages in their proper mystical order, failing if the rules
+    /// binding them form a cycle
+    pub fn arrange_pages(self) -> Result<Vec<u32>, CycleError> {
+        self.arrange_pages_with(CycleMode::Strict)
+            .map(|(sacred_sequence, _)| sacred_sequence)
+    }
+
+    /// Arrange pages in their proper mystical order. In [`CycleMode::Lenient`]
+    /// a cycle never fails the arrangement; it is handed back alongside the
+    /// pages so the caller can warn about it.
+    pub fn arrange_pages_with(
+        self,
+        mode: CycleMode,
+    ) -> Result<(Vec<u32>, Option<CycleError>), CycleError> {
+        let order = self.sacred_positions();
+        let mut sacred_sequence: Vec<_> = order.iter().map(|&at| self.pages[at]).collect();
+        let mut arranged = vec![false; self.pages.len()];
+        for &at in &order {
+            arranged[at] = true;
+        }
+
+        if sacred_sequence.len() == self.pages.len() {
+            return Ok((sacred_sequence, None));
+        }
+        let cycle = self.trace_cycle(&arranged);
+        if mode == CycleMode::Strict {
+            return Err(cycle);
+        }
+
+        // Handle any remaining pages (cycles) in original order
+        sacred_sequence.extend(
+            self.pages
+                .iter()
+                .zip(&arranged)
+                .filter(|(_, &arranged)| !arranged)
+                .map(|(&page, _)| page),
+        );
+
+        Ok((sacred_sequence, Some(cycle)))
+    }
+
+    /// The positions of the pages in sacred order, always taking the ready page
+    /// with the smallest [`precedence`](Self::precedence). Pages caught in or
+    /// behind a cycle are left out.
+    fn sacred_positions(&self) -> Vec<usize> {
+        let mut prerequisites_count = self.prerequisites_count.clone();
+        let mut order = Vec::with_capacity(self.pages.len());
+        let mut ready_pages: BinaryHeap<_> = (0..self.pages.len())
+            .filter(|&at| prerequisites_count[at] == 0)
+            .map(|at| Reverse(self.precedence(at)))
+            .collect();
+
+        while let Some(Reverse((_, at))) = ready_pages.pop() {
+            order.push(at);
+
+            for &next in &self.dependents[at] {
+                prerequisites_count[next] -= 1;
+                if prerequisites_count[next] == 0 {
+                    ready_pages.push(Reverse(self.precedence(next)));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Find a cycle among the pages the sort could not arrange. Every one of them
+    /// still waits on another unarranged page, so walking back through those
+    /// prerequisites must eventually revisit a page.
+    fn trace_cycle(&self, arranged: &[bool]) -> CycleError {
+        let waiting_on = |at: usize| {
+            (0..self.pages.len())
+                .find(|&before| !arranged[before] && self.dependents[before].contains(&at))
+                .expect("an unarranged page waits on another unarranged page")
+        };
+
+        let mut visited = vec![false; self.pages.len()];
+        let mut trail = Vec::new();
+        let mut at = arranged.iter().position(|&arranged| !arranged).unwrap();
+        while !visited[at] {
+            visited[at] = true;
+            trail.push(at);
+            at = waiting_on(at);
+        }
+
+        // The trail runs backwards against the rules; the cycle is its tail from `at`.
+        let start = trail.iter().position(|&step| step == at).unwrap();
+        let mut ring: Vec<_> = trail[start..].iter().map(|&at| self.pages[at]).collect();
+        ring.reverse();
+        let lore = (0..ring.len())
+            .map(|i| PrinterLore {
+                prerequisite: ring[i],
+                dependent: ring[(i + 1) % ring.len()],
+            })
+            .collect();
+
+        CycleError { pages: ring, lore }
+    }
+}
+
+/// A scroll from the printer's queue: the sacred rules, then the updates awaiting print
+#[derive(Debug, Default, Clone)]
+pub struct PrinterManual {
+    pub lore: Vec<PrinterLore>, // The ordering rules, in the order they were written
+    pub updates: Vec<Vec<u32>>, // The page numbers of each pending update
+}
+
+impl PrinterManual {
+    /// Unroll a scroll whose rules and updates are separated by a blank line
+    pub fn unroll(ancient_scroll: &str) -> Self {
+        let mut sections = ancient_scroll.split("\n\n");
+        let lore = sections
+            .next()
+            .map(interpret_printer_lore)
+            .unwrap_or_default();
+        let updates = sections
+            .next()
+            .map(decode_update_requests)
+            .unwrap_or_default();
+        Self { lore, updates }
+    }
+}
+
+/// Diagnose and repair the enchanted printer's update sequence. Pages bound in
+/// a cycle are printed as queued, and the cycle is kept as a warning
+pub fn diagnose_enchanted_printer(ancient_scroll: &str) -> PrinterDiagnosis {
+    diagnose_enchanted_printer_with(ancient_scroll, CycleMode::Lenient)
+        .expect("the lenient printer never jams")
+}
+
+/// Diagnose and repair the enchanted printer's update sequence, treating
+/// cycles in the rules as `mode` asks. Cycles tolerated in lenient mode are
+/// collected in [`PrinterDiagnosis::warnings`]
+pub fn diagnose_enchanted_printer_with(
+    ancient_scroll: &str,
+    mode: CycleMode,
+) -> Result<PrinterDiagnosis, CycleError> {
+    let PrinterManual { lore, updates } = PrinterManual::unroll(ancient_scroll);
+    let tome = PrinterTome::inscribe(&lore);
+    let mut diagnosis = PrinterDiagnosis::default();
+
+    for update in &updates {
+        if respects_sacred_order(update, &tome) {
+            diagnosis.properly_ordered_sum += divine_middle_page(update);
+        } else {
+            let keeper = EnchantedOrderKeeper::summon(update, &tome);
+            let (arranged, cycle) = keeper.arrange_pages_with(mode)?;
+            diagnosis.reordered_pages_sum += divine_middle_page(&arranged);
+            diagnosis.warnings.extend(cycle);
+        }
+    }
+
+    Ok(diagnosis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MYSTICAL_TEST_INPUT: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    #[test]
+    fn test_printer_diagnosis() {
+        let diagnosis = diagnose_enchanted_printer(MYSTICAL_TEST_INPUT);
+        assert_eq!(diagnosis.properly_ordered_sum, 143);
+        assert_eq!(diagnosis.reordered_pages_sum, 123);
+    }
+
+    #[test]
+    fn test_page_arrangement() {
+        let tome = PrinterTome::inscribe(&PrinterManual::unroll(MYSTICAL_TEST_INPUT).lore);
+
+        let keeper = EnchantedOrderKeeper::summon(&[75, 97, 47, 61, 53], &tome);
+        assert_eq!(keeper.arrange_pages().unwrap(), vec![97, 75, 47, 61, 53]);
+
+        let keeper = EnchantedOrderKeeper::summon(&[61, 13, 29], &tome);
+        assert_eq!(keeper.arrange_pages().unwrap(), vec![61, 29, 13]);
+
+        let keeper = EnchantedOrderKeeper::summon(&[97, 13, 75, 29, 47], &tome);
+        assert_eq!(keeper.arrange_pages().unwrap(), vec![97, 75, 47, 29, 13]);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        // 13 → 29 → 61 → 13 loops; 47 and 75 hang off the loop but are not part of it.
+        let lore = interpret_printer_lore("13|29\n29|61\n61|13\n75|47\n61|47");
+        let tome = PrinterTome::inscribe(&lore);
+        let pages = [47, 61, 75, 13, 29];
+
+        let error = EnchantedOrderKeeper::summon(&pages, &tome)
+            .arrange_pages()
+            .unwrap_err();
+        assert_eq!(error.pages, vec![13, 29, 61]);
+        assert_eq!(error.lore, interpret_printer_lore("13|29\n29|61\n61|13"));
+        assert_eq!(
+            error.to_string(),
+            "pages 13 → 29 → 61 → 13 form a cycle under rules 13|29, 29|61, 61|13"
+        );
+
+        // Leniently, the stuck pages keep their original order after the rest.
+        let (arranged, cycle) = EnchantedOrderKeeper::summon(&pages, &tome)
+            .arrange_pages_with(CycleMode::Lenient)
+            .unwrap();
+        assert_eq!(arranged, vec![75, 47, 61, 13, 29]);
+        assert_eq!(cycle, Some(error));
+
+        // A page that must precede itself is a cycle of one.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("5|5"));
+        let error = EnchantedOrderKeeper::summon(&[5, 6], &tome)
+            .arrange_pages()
+            .unwrap_err();
+        assert_eq!(error.pages, vec![5]);
+
+        // The diagnosis falls back by default; failing on a cycle is opt-in.
+        let scroll = "13|29\n29|13\n\n29,13,7\n1,2,3";
+        assert!(diagnose_enchanted_printer_with(scroll, CycleMode::Strict).is_err());
+        let diagnosis = diagnose_enchanted_printer(scroll);
+        assert_eq!(
+            diagnose_enchanted_printer_with(scroll, CycleMode::default()).as_ref(),
+            Ok(&diagnosis)
+        );
+        assert_eq!(diagnosis.properly_ordered_sum, 2);
+        assert_eq!(diagnosis.reordered_pages_sum, 29);
+        assert_eq!(diagnosis.warnings.len(), 1);
+        assert_eq!(diagnosis.warnings[0].pages, vec![13, 29]);
+    }
+
+    #[test]
+    fn test_validation() {
+        let PrinterManual { lore, updates } = PrinterManual::unroll(MYSTICAL_TEST_INPUT);
+        assert_eq!(lore.len(), 21);
+        assert_eq!(
+            lore[0],
+            PrinterLore {
+                prerequisite: 47,
+                dependent: 53,
+            }
+        );
+        let tome = PrinterTome::inscribe(&lore);
+        let valid: Vec<bool> = updates
+            .iter()
+            .map(|update| respects_sacred_order(update, &tome))
+            .collect();
+        assert_eq!(valid, [true, true, true, false, false, false]);
+        assert_eq!(divine_middle_page(&updates[0]), 61);
+
+        let violations = find_violations(&updates[5], &tome);
+        assert_eq!(violations.len(), 4);
+        assert_eq!(
+            violations[0],
+            OrderViolation {
+                early_page: 13,
+                early_position: 1,
+                late_page: 75,
+                late_position: 2,
+                broken_rule: PrinterLore {
+                    prerequisite: 75,
+                    dependent: 13,
+                },
+            }
+        );
+        // Display counts positions from 1.
+        assert_eq!(
+            violations[0].to_string(),
+            "page 13 at position 2 precedes page 75 at position 3, breaking rule 75|13"
+        );
+        assert_eq!(violations[3].broken_rule.to_string(), "47|29");
+        assert!(updates[..3]
+            .iter()
+            .all(|update| find_violations(update, &tome).is_empty()));
+    }
+
+    #[test]
+    fn test_violations_between_distant_pages() {
+        // No rule binds neighbours, yet 3 is printed before 1 against the rule 1|3.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|3"));
+        let update = [3, 2, 1];
+        assert!(!respects_sacred_order(&update, &tome));
+
+        let violations = find_violations(&update, &tome);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "page 3 at position 1 precedes page 1 at position 3, breaking rule 1|3"
+        );
+        assert!(respects_sacred_order(&[1, 2, 3], &tome));
+    }
+
+    #[test]
+    fn test_tome_spans_many_pages() {
+        // A chain of rules over more pages than fit in one word of the matrix.
+        let lore: Vec<_> = (1000..1200)
+            .map(|page| PrinterLore {
+                prerequisite: page,
+                dependent: page + 1,
+            })
+            .collect();
+        let tome = PrinterTome::inscribe(&lore);
+        assert_eq!(tome.page_count(), 201);
+        assert!(tome.must_precede(1000, 1001));
+        assert!(tome.must_precede(1199, 1200));
+        assert!(!tome.must_precede(1001, 1000));
+        assert!(!tome.must_precede(1000, 1002));
+        assert!(tome.violates_sacred_order(1130, 1129));
+
+        // Pages no rule names are free to go anywhere.
+        assert!(!tome.must_precede(7, 1000));
+        assert!(respects_sacred_order(&[1150, 7, 1151], &tome));
+
+        let keeper = EnchantedOrderKeeper::summon(&[1199, 5, 1198, 1197], &tome);
+        assert_eq!(keeper.arrange_pages().unwrap(), vec![5, 1197, 1198, 1199]);
+    }
+
+    #[test]
+    fn test_display_without_redaction() {
+        let diagnosis = PrinterDiagnosis {
+            properly_ordered_sum: 143,
+            reordered_pages_sum: 123,
+            ..PrinterDiagnosis::default()
+        };
+        let output = format!("{}", diagnosis);
+        assert!(output.contains("143"));
+        assert!(output.contains("123"));
+    }
+
+    #[test]
+    fn test_display_with_redaction() {
+        let diagnosis = PrinterDiagnosis {
+            properly_ordered_sum: 143,
+            reordered_pages_sum: 123,
+            ..PrinterDiagnosis::default()
+        };
+        let output = format!("{:-}", diagnosis);
+        assert!(!output.contains("143"));
+        assert!(!output.contains("123"));
+        assert!(output.contains("*****"));
+    }
+}
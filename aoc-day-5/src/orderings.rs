@@ -0,0 +1,275 @@
+//! Every order the sacred rules allow for an update.
+//!
+//! When the rules do not bind every pair of an update's pages, several orders may
+//! satisfy them. These are walked by backtracking in the Order Keeper's
+//! [`TieBreak`](crate::TieBreak) order, so the first one is always the order
+//! [`EnchantedOrderKeeper::arrange_pages`] picks, and counted by memoising over
+//! the sets of pages already printed.
+//!
+//! A page repeated in an update is bound by the same rules at every position,
+//! so its copies are interchangeable. Copies are only ever printed in rank
+//! order, which yields and counts each distinct order once.
+
+use crate::EnchantedOrderKeeper;
+use std::collections::HashMap;
+use std::iter::FusedIterator;
+
+impl EnchantedOrderKeeper {
+    /// Every valid order of the update's pages, lazily, in tie-break order.
+    /// Rules that form a cycle allow no order at all.
+    pub fn orderings(&self) -> SacredOrderings<'_> {
+        let mut ranked: Vec<_> = (0..self.pages.len()).collect();
+        ranked.sort_by_key(|&at| self.precedence(at));
+        let acyclic = self.sacred_positions().len() == self.pages.len();
+
+        SacredOrderings {
+            keeper: self,
+            twins: self.earlier_twins(&ranked),
+            ranked,
+            waiting: self.prerequisites_count.clone(),
+            placed: vec![false; self.pages.len()],
+            chosen: Vec::with_capacity(self.pages.len()),
+            cursors: vec![0; self.pages.len() + 1],
+            emitted: false,
+            done: !acyclic,
+        }
+    }
+
+    /// Whether exactly one order of the update's pages satisfies the rules.
+    pub fn has_unique_order(&self) -> bool {
+        self.orderings().take(2).count() == 1
+    }
+
+    /// How many orders of the update's pages satisfy the rules, saturating at
+    /// `u128::MAX`. The work grows with the number of distinct sets of pages
+    /// that can be printed first, so loosely bound updates take longest.
+    pub fn count_orderings(&self) -> u128 {
+        if self.sacred_positions().len() != self.pages.len() {
+            return 0;
+        }
+        let twins = self.earlier_twins(&(0..self.pages.len()).collect::<Vec<_>>());
+        let mut waiting = self.prerequisites_count.clone();
+        let mut printed = vec![0u64; self.pages.len().div_ceil(64)];
+        self.count_from(&twins, &mut waiting, &mut printed, &mut HashMap::new())
+    }
+
+    /// For each position, the previous position in `order` holding the same
+    /// page, which must be printed first so that copies never trade places.
+    fn earlier_twins(&self, order: &[usize]) -> Vec<Option<usize>> {
+        let mut last_seen = HashMap::new();
+        let mut twins = vec![None; self.pages.len()];
+        for &at in order {
+            twins[at] = last_seen.insert(self.pages[at], at);
+        }
+        twins
+    }
+
+    /// Count the ways to finish an order once the pages in `printed` are out.
+    fn count_from(
+        &self,
+        twins: &[Option<usize>],
+        waiting: &mut [usize],
+        printed: &mut Vec<u64>,
+        memo: &mut HashMap<Vec<u64>, u128>,
+    ) -> u128 {
+        if let Some(&count) = memo.get(printed) {
+            return count;
+        }
+
+        let mut count = 0u128;
+        let mut finished = true;
+        for at in 0..self.pages.len() {
+            if printed[at / 64] & (1 << (at % 64)) != 0 {
+                continue;
+            }
+            finished = false;
+            let twin_waiting =
+                twins[at].is_some_and(|twin| printed[twin / 64] & (1 << (twin % 64)) == 0);
+            if waiting[at] != 0 || twin_waiting {
+                continue;
+            }
+
+            printed[at / 64] |= 1 << (at % 64);
+            for &next in &self.dependents[at] {
+                waiting[next] -= 1;
+            }
+            count = count.saturating_add(self.count_from(twins, waiting, printed, memo));
+            for &next in &self.dependents[at] {
+                waiting[next] += 1;
+            }
+            printed[at / 64] &= !(1 << (at % 64));
+        }
+        if finished {
+            count = 1;
+        }
+
+        memo.insert(printed.clone(), count);
+        count
+    }
+}
+
+/// The valid orders of an update's pages, found one at a time by backtracking.
+/// The Order Keeper tries each ready page in turn, then steps back to try the next.
+#[derive(Debug, Clone)]
+pub struct SacredOrderings<'k> {
+    keeper: &'k EnchantedOrderKeeper,
+    twins: Vec<Option<usize>>, // Earlier-ranked copy of each repeated page
+    ranked: Vec<usize>,        // Positions in the order ready pages are tried
+    waiting: Vec<usize>,       // Unprinted prerequisites of each page
+    placed: Vec<bool>,         // Whether each page is in the order so far
+    chosen: Vec<usize>,        // Positions of the pages in the order so far
+    cursors: Vec<usize>,       // For each depth, the rank to try next
+    emitted: bool,             // Whether the complete order in `chosen` was yielded
+    done: bool,                // Whether every order was yielded
+}
+
+impl SacredOrderings<'_> {
+    /// Print the page at `at` next.
+    fn place(&mut self, at: usize) {
+        self.placed[at] = true;
+        self.chosen.push(at);
+        for &next in &self.keeper.dependents[at] {
+            self.waiting[next] -= 1;
+        }
+    }
+
+    /// Take back the last page printed, or finish if none are left to take back.
+    fn retreat(&mut self) {
+        let Some(at) = self.chosen.pop() else {
+            self.done = true;
+            return;
+        };
+        self.placed[at] = false;
+        for &next in &self.keeper.dependents[at] {
+            self.waiting[next] += 1;
+        }
+    }
+}
+
+impl Iterator for SacredOrderings<'_> {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Vec<u32>> {
+        let len = self.ranked.len();
+        while !self.done {
+            let depth = self.chosen.len();
+            if depth == len {
+                if !self.emitted {
+                    self.emitted = true;
+                    return Some(
+                        self.chosen
+                            .iter()
+                            .map(|&at| self.keeper.pages[at])
+                            .collect(),
+                    );
+                }
+                self.retreat();
+                continue;
+            }
+
+            let ready = (self.cursors[depth]..len).find(|&rank| {
+                let at = self.ranked[rank];
+                !self.placed[at]
+                    && self.waiting[at] == 0
+                    && self.twins[at].is_none_or(|twin| self.placed[twin])
+            });
+            match ready {
+                Some(rank) => {
+                    self.cursors[depth] = rank + 1;
+                    self.cursors[depth + 1] = 0;
+                    self.place(self.ranked[rank]);
+                    self.emitted = false;
+                }
+                None => self.retreat(),
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for SacredOrderings<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{interpret_printer_lore, EnchantedOrderKeeper, PrinterTome, TieBreak};
+
+    #[test]
+    fn test_enumeration() {
+        // 1 before 2 and 3, each of those before 4: two orders.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|2\n1|3\n2|4\n3|4"));
+        let keeper = EnchantedOrderKeeper::summon(&[4, 3, 2, 1], &tome);
+        let orders: Vec<_> = keeper.orderings().collect();
+        assert_eq!(orders, vec![vec![1, 3, 2, 4], vec![1, 2, 3, 4]]);
+        assert_eq!(keeper.count_orderings(), 2);
+        assert!(!keeper.has_unique_order());
+
+        // By smallest page, 2 comes first instead.
+        let keeper = keeper.tie_break(TieBreak::SmallestPage);
+        assert_eq!(keeper.orderings().next(), Some(vec![1, 2, 3, 4]));
+        assert_eq!(keeper.clone().arrange_pages().unwrap(), vec![1, 2, 3, 4]);
+
+        // A chain leaves no choice.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|2\n2|3"));
+        let keeper = EnchantedOrderKeeper::summon(&[3, 1, 2], &tome);
+        assert!(keeper.has_unique_order());
+        assert_eq!(keeper.count_orderings(), 1);
+    }
+
+    #[test]
+    fn test_counting_agrees_with_enumeration() {
+        // Six pages in any order, so long as 10 comes before 20.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("10|20"));
+        let keeper = EnchantedOrderKeeper::summon(&[20, 1, 2, 10, 3, 4], &tome);
+        assert_eq!(keeper.count_orderings(), 360);
+        assert_eq!(keeper.orderings().count(), 360);
+        let first = keeper.orderings().next().unwrap();
+        assert_eq!(first, keeper.clone().arrange_pages().unwrap());
+        assert_eq!(first, vec![1, 2, 10, 20, 3, 4]);
+
+        // An empty update has exactly one order; a cycle has none.
+        let keeper = EnchantedOrderKeeper::summon(&[], &tome);
+        assert_eq!(
+            keeper.orderings().collect::<Vec<_>>(),
+            vec![Vec::<u32>::new()]
+        );
+        assert_eq!(keeper.count_orderings(), 1);
+
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|2\n2|1"));
+        let keeper = EnchantedOrderKeeper::summon(&[1, 2, 3], &tome);
+        assert_eq!(keeper.orderings().count(), 0);
+        assert_eq!(keeper.count_orderings(), 0);
+        assert!(!keeper.has_unique_order());
+    }
+
+    #[test]
+    fn test_repeated_pages() {
+        // Copies of a page cannot be told apart, so [1, 1] has a single order.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|2"));
+        let keeper = EnchantedOrderKeeper::summon(&[1, 1], &tome);
+        assert_eq!(keeper.orderings().collect::<Vec<_>>(), vec![vec![1, 1]]);
+        assert_eq!(keeper.count_orderings(), 1);
+        assert!(keeper.has_unique_order());
+
+        // Both copies of 1 before 2, with 3 anywhere: four distinct orders.
+        let keeper = EnchantedOrderKeeper::summon(&[2, 3, 1, 1], &tome);
+        let orders: Vec<_> = keeper.orderings().collect();
+        assert_eq!(
+            orders,
+            vec![
+                vec![3, 1, 1, 2],
+                vec![1, 3, 1, 2],
+                vec![1, 1, 2, 3],
+                vec![1, 1, 3, 2],
+            ]
+        );
+        assert_eq!(keeper.count_orderings(), 4);
+        assert_eq!(orders[0], keeper.clone().arrange_pages().unwrap());
+
+        let keeper = keeper.tie_break(TieBreak::SmallestPage);
+        assert_eq!(keeper.orderings().count(), 4);
+        assert_eq!(
+            keeper.orderings().next(),
+            Some(keeper.clone().arrange_pages().unwrap())
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Repairing an update with as few page moves as possible.
+//!
+//! A page moves when it is lifted out of the queue and slotted back in elsewhere.
+//! The pages that stay put keep their relative order, so they must never have a
+//! rule, direct or through other pages of the update, demanding a later one come
+//! first. Two pages in that relation conflict; conflicts are transitive, so the
+//! largest conflict-free set is a maximum antichain, found by Dilworth's theorem
+//! from a maximum matching. The moved pages are then reinserted by a sort that
+//! keeps the rest in line.
+
+use crate::{CycleError, EnchantedOrderKeeper};
+
+/// A page lifted out of an update and reinserted elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovedPage {
+    pub page: u32,   // The page that moved
+    pub from: usize, // Its index in the original update
+    pub to: usize,   // Its index in the repaired update
+}
+
+/// An update put in a valid order with the fewest pages moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRepair {
+    pub pages: Vec<u32>,       // The repaired update
+    pub moved: Vec<MovedPage>, // The pages that moved, by original position
+}
+
+impl EnchantedOrderKeeper {
+    /// Put the update in a valid order by moving as few pages as possible,
+    /// leaving the rest in their original relative order. Ready pages are
+    /// reinserted in tie-break order. Fails if the rules binding the pages form
+    /// a cycle, since then no order is valid.
+    pub fn repair_pages(&self) -> Result<PageRepair, CycleError> {
+        let order = self.sacred_positions();
+        if order.len() != self.pages.len() {
+            let mut arranged = vec![false; self.pages.len()];
+            for at in order {
+                arranged[at] = true;
+            }
+            return Err(self.trace_cycle(&arranged));
+        }
+
+        // Chain the pages that stay, so the sort cannot reorder them.
+        let kept = self.largest_consistent_subsequence();
+        let mut chained = self.clone();
+        for pair in kept.windows(2) {
+            chained.dependents[pair[0]].push(pair[1]);
+            chained.prerequisites_count[pair[1]] += 1;
+        }
+        let order = chained.sacred_positions();
+
+        let mut destination = vec![0; self.pages.len()];
+        for (to, &at) in order.iter().enumerate() {
+            destination[at] = to;
+        }
+        let mut staying = vec![false; self.pages.len()];
+        for &at in &kept {
+            staying[at] = true;
+        }
+
+        Ok(PageRepair {
+            pages: order.iter().map(|&at| self.pages[at]).collect(),
+            moved: (0..self.pages.len())
+                .filter(|&at| !staying[at])
+                .map(|at| MovedPage {
+                    page: self.pages[at],
+                    from: at,
+                    to: destination[at],
+                })
+                .collect(),
+        })
+    }
+
+    /// The positions of the most pages that can stay in their original relative
+    /// order, ascending.
+    fn largest_consistent_subsequence(&self) -> Vec<usize> {
+        let len = self.pages.len();
+
+        // before[a][b]: the page at a must be printed before the page at b.
+        let mut before = vec![vec![false; len]; len];
+        for (at, dependents) in self.dependents.iter().enumerate() {
+            for &next in dependents {
+                before[at][next] = true;
+            }
+        }
+        for via in 0..len {
+            let onward = before[via].clone();
+            for row in before.iter_mut().filter(|row| row[via]) {
+                for (reach, &through) in row.iter_mut().zip(&onward) {
+                    *reach |= through;
+                }
+            }
+        }
+
+        // Page a conflicts with a later page b when b must come first.
+        let conflicts: Vec<Vec<usize>> = (0..len)
+            .map(|at| (at + 1..len).filter(|&later| before[later][at]).collect())
+            .collect();
+
+        let mut matched = vec![None; len];
+        for at in 0..len {
+            augment(at, &conflicts, &mut matched, &mut vec![false; len]);
+        }
+
+        // König: walk alternating paths from the unmatched left side. A page whose
+        // left copy is reached and whose right copy is not lies in no cover.
+        let mut matched_left = vec![false; len];
+        for &left in matched.iter().flatten() {
+            matched_left[left] = true;
+        }
+        let mut left_reached = vec![false; len];
+        let mut right_reached = vec![false; len];
+        let mut stack: Vec<usize> = (0..len).filter(|&at| !matched_left[at]).collect();
+        for &at in &stack {
+            left_reached[at] = true;
+        }
+        while let Some(left) = stack.pop() {
+            for &right in &conflicts[left] {
+                if !right_reached[right] {
+                    right_reached[right] = true;
+                    if let Some(next) = matched[right] {
+                        if !left_reached[next] {
+                            left_reached[next] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        (0..len)
+            .filter(|&at| left_reached[at] && !right_reached[at])
+            .collect()
+    }
+}
+
+/// Kuhn's augmenting path search: try to match `left` to a page it conflicts
+/// with, rematching earlier pages if need be.
+fn augment(
+    left: usize,
+    conflicts: &[Vec<usize>],
+    matched: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &right in &conflicts[left] {
+        if seen[right] {
+            continue;
+        }
+        seen[right] = true;
+        if matched[right].is_none_or(|other| augment(other, conflicts, matched, seen)) {
+            matched[right] = Some(left);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interpret_printer_lore, respects_sacred_order, PrinterManual, PrinterTome};
+
+    /// The fewest moves any valid order needs: the pages outside the longest
+    /// subsequence it shares with the original.
+    fn fewest_moves(keeper: &EnchantedOrderKeeper, original: &[u32]) -> usize {
+        let longest_common = |order: &[u32]| {
+            let mut table = vec![vec![0; order.len() + 1]; original.len() + 1];
+            for i in 0..original.len() {
+                for j in 0..order.len() {
+                    table[i + 1][j + 1] = if original[i] == order[j] {
+                        table[i][j] + 1
+                    } else {
+                        table[i][j + 1].max(table[i + 1][j])
+                    };
+                }
+            }
+            table[original.len()][order.len()]
+        };
+        keeper
+            .orderings()
+            .map(|order| original.len() - longest_common(&order))
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_repair_moves_few_pages() {
+        let PrinterManual { lore, updates } = PrinterManual::unroll(
+            "47|53\n97|13\n97|61\n97|47\n75|29\n61|13\n75|53\n29|13\n97|29\n53|29\n61|53\n\
+             97|53\n61|29\n47|13\n75|47\n97|75\n47|61\n75|61\n47|29\n75|13\n53|13\n\n\
+             75,97,47,61,53\n61,13,29\n97,13,75,29,47",
+        );
+        let tome = PrinterTome::inscribe(&lore);
+
+        let repair = EnchantedOrderKeeper::summon(&updates[0], &tome)
+            .repair_pages()
+            .unwrap();
+        // Swapping 75 and 97 takes a single move, of either page.
+        assert_eq!(repair.pages, vec![97, 75, 47, 61, 53]);
+        assert_eq!(
+            repair.moved,
+            vec![MovedPage {
+                page: 75,
+                from: 0,
+                to: 1,
+            }]
+        );
+
+        for update in &updates {
+            let keeper = EnchantedOrderKeeper::summon(update, &tome);
+            let repair = keeper.repair_pages().unwrap();
+            assert!(respects_sacred_order(&repair.pages, &tome));
+            assert_eq!(repair.moved.len(), fewest_moves(&keeper, update));
+        }
+    }
+
+    #[test]
+    fn test_repair_with_loose_rules() {
+        // Only 1 must precede 3, and 3 must precede 5: pages 2 and 4 are free.
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|3\n3|5"));
+
+        // Only 5 moves; 4, 1, 2 and 3 keep their order.
+        let update = [5, 4, 1, 2, 3];
+        let keeper = EnchantedOrderKeeper::summon(&update, &tome);
+        let repair = keeper.repair_pages().unwrap();
+        assert_eq!(repair.pages, vec![4, 1, 2, 3, 5]);
+        assert_eq!(repair.moved.len(), 1);
+        assert_eq!(repair.moved[0].page, 5);
+        assert_eq!(repair.moved.len(), fewest_moves(&keeper, &update));
+
+        // 5 before 1 conflicts through 3, even with no rule between them.
+        let update = [3, 5, 1];
+        let keeper = EnchantedOrderKeeper::summon(&update, &tome);
+        let repair = keeper.repair_pages().unwrap();
+        assert_eq!(repair.pages, vec![1, 3, 5]);
+        assert_eq!(repair.moved.len(), 1);
+
+        // Valid updates are left alone, and cycles cannot be repaired.
+        let keeper = EnchantedOrderKeeper::summon(&[1, 2, 3], &tome);
+        assert!(keeper.repair_pages().unwrap().moved.is_empty());
+        let tome = PrinterTome::inscribe(&interpret_printer_lore("1|2\n2|1"));
+        assert!(EnchantedOrderKeeper::summon(&[1, 2], &tome)
+            .repair_pages()
+            .is_err());
+    }
+}